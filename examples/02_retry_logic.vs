@@ -1,33 +1,30 @@
 // 02_retry_logic.vs
-// Demonstration of control flow (while loops) mimicking a database connection retry.
+// Demonstration of the `retry` construct mimicking a database connection retry.
+// The block's resulting V-Bit decides: error/loading retry, on stops.
 
 print("--- DATABASE CONNECTION MODULE ---");
 
-var max_retries := 5;
-var attempt := 0;
-var connected := 0; // 0: False, 1: True
-
-// Loop until connected or retries exhausted
-while attempt < max_retries {
-    attempt += 1;
-    print("Attempting connection to DB_PRIMARY...");
-    
-    // Simulate latency
-    var _ := Time.sleep(500);
+var connection := retry 5 backoff exponential(200ms, max: 1s) jitter {
+    print("Attempting connection to DB_PRIMARY... Attempt:");
+    print(attempt);
 
     // Simulation: Connection succeeds on attempt 3
+    var handshake := error;
     if attempt == 3 {
         print(">> Handshake received. Connection established.");
-        connected := 1;
-        // Break mechanism: force loop exit condition
-        attempt := 10; 
+        handshake = on;
     } else {
         print(">> Connection timed out. Retrying...");
     }
-}
+    var result := handshake;
+} until on;
 
-if connected == 1 {
-    print("SUCCESS: Database is ready for queries.");
-} else {
-    print("CRITICAL: Could not reach database after max retries.");
-}
\ No newline at end of file
+match connection {
+    on => {
+        print("SUCCESS: Database is ready for queries.");
+    }
+    error => {
+        print("CRITICAL: Could not reach database after max retries.");
+        print(connection);
+    }
+}
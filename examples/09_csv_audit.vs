@@ -0,0 +1,34 @@
+// 09_csv_audit.vs
+// DEMO: Script 08 with real data.
+// Csv.read never aborts on a broken row: the row becomes an `error` in place.
+
+print("--- FINANCIAL AUDIT SYSTEM v3.0 (CSV) ---");
+
+// 1. Load the dataset (data/sales.csv has a truncated row for region Este)
+var rows := Csv.read(Path.join(Path.script_dir(), "data/sales.csv"));
+
+// 2. Naive total: the corrupted row taints the whole result
+var total_revenue := 0;
+for row in rows {
+    total_revenue += row.revenue;
+}
+print("Naive total:");
+print(total_revenue);
+
+// 3. Isolate the bad rows and keep the valid ones
+var safe_total := 0;
+for row in rows {
+    if row == error {
+        print("⚠️ Skipping corrupted row: ${row}");
+    } else {
+        safe_total += row.revenue;
+    }
+}
+
+print("✅ Recovered Valid Revenue: ${safe_total}");
+
+// 4. Persist the clean report (Csv.write skips error rows and says how many)
+var saved := Csv.write("/tmp/vaso_sales_clean.csv", rows, {columns: ["region", "revenue"]});
+print("Clean report written: ${saved}");
+
+print("\n--- SYSTEM STATUS: ONLINE (No Crashes) ---");
@@ -0,0 +1,104 @@
+use crate::types::VasoType;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, SecondsFormat, SubsecRound, TimeZone, Utc};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+// --- FECHAS: DateTime con offset fijo (UTC por defecto) ---
+
+// Precision de milisegundos, igual que Duration
+pub fn now_utc() -> DateTime<FixedOffset> {
+    Utc::now().trunc_subsecs(3).fixed_offset()
+}
+
+static START: OnceLock<Instant> = OnceLock::new();
+
+// main lo llama al arrancar para que el reloj monotono cuente desde el inicio del motor
+pub fn start_clock() {
+    START.get_or_init(Instant::now);
+}
+
+// Reloj monotono: Duration desde que arranco el motor (no salta con NTP ni cambios de hora)
+pub fn monotonic_ms() -> u64 {
+    START.get_or_init(Instant::now).elapsed().as_millis() as u64
+}
+
+pub fn to_rfc3339(dt: &DateTime<FixedOffset>) -> String {
+    dt.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+// strftime: "%Y-%m-%d %H:%M:%S". Un especificador invalido es Error, nunca panic.
+pub fn format(dt: &DateTime<FixedOffset>, fmt: &str) -> VasoType {
+    let items: Vec<Item> = StrftimeItems::new(fmt).collect();
+    if items.iter().any(|item| matches!(item, Item::Error)) {
+        return VasoType::VBit(3, format!("Invalid time format '{}'", fmt));
+    }
+    VasoType::Str(dt.format_with_items(items.into_iter()).to_string())
+}
+
+// Sin formato: RFC 3339. Con formato: strftime; si el formato no trae offset se asume UTC,
+// y si no trae hora se asume medianoche.
+pub fn parse(text: &str, fmt: Option<&str>) -> VasoType {
+    let result = match fmt {
+        None => DateTime::parse_from_rfc3339(text.trim()).map_err(|e| e.to_string()),
+        Some(f) => DateTime::parse_from_str(text, f)
+            .or_else(|_| NaiveDateTime::parse_from_str(text, f).map(|n| Utc.from_utc_datetime(&n).fixed_offset()))
+            .or_else(|_| NaiveDate::parse_from_str(text, f).map(|d| Utc.from_utc_datetime(&d.and_time(Default::default())).fixed_offset()))
+            .map_err(|e| e.to_string()),
+    };
+    match result {
+        Ok(dt) => VasoType::DateTime(dt),
+        Err(e) => VasoType::VBit(3, format!("Time Parse Error: '{}': {}", text, e)),
+    }
+}
+
+// "+02:00", "-0530", "Z" / "UTC"
+pub fn parse_offset(text: &str) -> Option<FixedOffset> {
+    let t = text.trim();
+    if t.eq_ignore_ascii_case("z") || t.eq_ignore_ascii_case("utc") {
+        return FixedOffset::east_opt(0);
+    }
+    let (sign, rest) = match t.chars().next()? {
+        '+' => (1, &t[1..]),
+        '-' => (-1, &t[1..]),
+        _ => return None,
+    };
+    let digits: String = rest.chars().filter(|c| *c != ':').collect();
+    if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_digit()) { return None; }
+    let hours: i32 = digits[..2].parse().ok()?;
+    let minutes: i32 = digits[2..].parse().ok()?;
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+pub fn from_unix(secs: i64) -> Option<DateTime<FixedOffset>> {
+    DateTime::from_timestamp(secs, 0).map(|dt| dt.fixed_offset())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_defaults_to_utc_midnight() {
+        let VasoType::DateTime(dt) = parse("01/03/2025", Some("%d/%m/%Y")) else { panic!() };
+        assert_eq!(to_rfc3339(&dt), "2025-03-01T00:00:00Z");
+        let VasoType::DateTime(dt) = parse("2025-03-01T10:00:00.250+02:00", None) else { panic!() };
+        assert_eq!(to_rfc3339(&dt), "2025-03-01T10:00:00.250+02:00");
+        assert!(matches!(parse("yesterday", None), VasoType::VBit(3, _)));
+    }
+
+    #[test]
+    fn invalid_format_specifier_is_an_error() {
+        let dt = from_unix(0).unwrap();
+        assert_eq!(format(&dt, "%Y-%m-%d"), VasoType::Str("1970-01-01".to_string()));
+        assert!(matches!(format(&dt, "%Q"), VasoType::VBit(3, _)));
+    }
+
+    #[test]
+    fn offsets() {
+        assert_eq!(parse_offset("+02:00"), FixedOffset::east_opt(7200));
+        assert_eq!(parse_offset("-0530"), FixedOffset::east_opt(-19800));
+        assert_eq!(parse_offset("Z"), FixedOffset::east_opt(0));
+        assert_eq!(parse_offset("2h"), None);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::types::VasoType;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+
+// --- VARIABLES DE ENTORNO (Env.get / Env.set / Env.load) ---
+// Env.set no toca el entorno real del proceso (set_var es unsafe con hilos vivos):
+// guarda un overlay que ven Env.get/Env.all y que se aplica a cada comando lanzado.
+// None = variable borrada con Env.unset.
+
+thread_local! {
+    static OVERLAY: RefCell<BTreeMap<String, Option<String>>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+pub fn get(name: &str) -> Option<String> {
+    match OVERLAY.with(|o| o.borrow().get(name).cloned()) {
+        Some(val) => val,
+        None => env::var(name).ok(),
+    }
+}
+
+pub fn set(name: &str, value: Option<String>) {
+    OVERLAY.with(|o| { o.borrow_mut().insert(name.to_string(), value); });
+}
+
+pub fn all() -> BTreeMap<String, String> {
+    let mut vars: BTreeMap<String, String> = env::vars().collect();
+    OVERLAY.with(|o| for (key, val) in o.borrow().iter() {
+        match val {
+            Some(v) => { vars.insert(key.clone(), v.clone()); },
+            None => { vars.remove(key); }
+        }
+    });
+    vars
+}
+
+// Lo que hay que aplicar a un Command para que vea el mismo entorno que el script
+pub fn overlay() -> Vec<(String, Option<String>)> {
+    OVERLAY.with(|o| o.borrow().iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+}
+
+// Env.load(".env"): KEY=value, `export KEY=value`, comentarios con #, comillas simples
+// (literal) y dobles (con \n, \t, \", \\). No pisa variables que ya existan salvo `force`.
+pub fn load(path: &str, force: bool) -> VasoType {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => return VasoType::VBit(3, format!("Env.load: '{}': {}", path, e))
+    };
+    let mut loaded = BTreeMap::new();
+    for (n, raw) in content.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') { continue; }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            return VasoType::VBit(3, format!("Env.load: {}:{}: expected KEY=value", path, n + 1));
+        };
+        let key = key.trim();
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return VasoType::VBit(3, format!("Env.load: {}:{}: invalid name '{}'", path, n + 1, key));
+        }
+        let value = match parse_value(value.trim()) {
+            Some(v) => v,
+            None => return VasoType::VBit(3, format!("Env.load: {}:{}: unterminated quote", path, n + 1))
+        };
+        if force || get(key).is_none() { set(key, Some(value.clone())); }
+        loaded.insert(key.to_string(), VasoType::Str(value));
+    }
+    VasoType::Map(loaded)
+}
+
+fn parse_value(raw: &str) -> Option<String> {
+    if let Some(rest) = raw.strip_prefix('\'') {
+        return rest.find('\'').map(|end| rest[..end].to_string());
+    }
+    if let Some(rest) = raw.strip_prefix('"') {
+        let mut out = String::new();
+        let mut chars = rest.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => return Some(out),
+                '\\' => match chars.next()? {
+                    'n' => out.push('\n'),
+                    't' => out.push('\t'),
+                    other => out.push(other),
+                },
+                c => out.push(c),
+            }
+        }
+        return None;
+    }
+    // Sin comillas: un " #" empieza un comentario
+    let value = match raw.find(" #") { Some(pos) => &raw[..pos], None => raw };
+    Some(value.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dotenv(name: &str, content: &str) -> String {
+        let path = env::temp_dir().join(format!("vaso-env-{}-{}", std::process::id(), name));
+        fs::write(&path, content).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn parses_values() {
+        assert_eq!(parse_value("plain value # note"), Some("plain value".to_string()));
+        assert_eq!(parse_value("a#b"), Some("a#b".to_string()));
+        assert_eq!(parse_value("'literal \\n $HOME'"), Some("literal \\n $HOME".to_string()));
+        assert_eq!(parse_value("\"line\\n\\ttab \\\"q\\\" \\\\\""), Some("line\n\ttab \"q\" \\".to_string()));
+        assert_eq!(parse_value("\"unterminated"), None);
+        assert_eq!(parse_value("'unterminated"), None);
+        assert_eq!(parse_value(""), Some(String::new()));
+    }
+
+    #[test]
+    fn loads_a_file() {
+        let path = dotenv("ok", "# comment\n\nexport VASO_T_A=1\nVASO_T_B = \"two words\" \nVASO_T_C='x'\n");
+        let mut expected = BTreeMap::new();
+        for (k, v) in [("VASO_T_A", "1"), ("VASO_T_B", "two words"), ("VASO_T_C", "x")] {
+            expected.insert(k.to_string(), VasoType::Str(v.to_string()));
+        }
+        assert_eq!(load(&path, false), VasoType::Map(expected));
+        assert_eq!(get("VASO_T_B"), Some("two words".to_string()));
+    }
+
+    #[test]
+    fn keeps_existing_variables_unless_forced() {
+        set("VASO_T_KEEP", Some("mine".to_string()));
+        let path = dotenv("keep", "VASO_T_KEEP=file\n");
+        load(&path, false);
+        assert_eq!(get("VASO_T_KEEP"), Some("mine".to_string()));
+        load(&path, true);
+        assert_eq!(get("VASO_T_KEEP"), Some("file".to_string()));
+    }
+
+    #[test]
+    fn reports_the_bad_line() {
+        let path = dotenv("bad", "A=1\nnot a pair\n");
+        assert_eq!(load(&path, false), VasoType::VBit(3, format!("Env.load: {}:2: expected KEY=value", path)));
+        let path = dotenv("name", "BAD-NAME=1\n");
+        assert_eq!(load(&path, false), VasoType::VBit(3, format!("Env.load: {}:1: invalid name 'BAD-NAME'", path)));
+        let path = dotenv("quote", "A=\"open\n");
+        assert_eq!(load(&path, false), VasoType::VBit(3, format!("Env.load: {}:1: unterminated quote", path)));
+    }
+}
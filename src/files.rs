@@ -0,0 +1,156 @@
+use crate::types::VasoType;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+
+// --- ARCHIVOS: File.exists / copy / move / glob / stat ... ---
+// Nunca hay panic: cualquier fallo del sistema es un Error con la operacion, la ruta y el error del SO.
+
+fn io_error(op: &str, path: &str, e: impl std::fmt::Display) -> VasoType {
+    VasoType::VBit(3, format!("File.{}: '{}': {}", op, path, e))
+}
+
+fn done() -> VasoType {
+    VasoType::VBit(1, "".to_string())
+}
+
+pub fn exists(path: &str) -> VasoType {
+    if Path::new(path).exists() { done() } else { VasoType::VBit(0, "".to_string()) }
+}
+
+pub fn append(path: &str, content: &str) -> VasoType {
+    let result = fs::OpenOptions::new().create(true).append(true).open(path)
+        .and_then(|mut file| file.write_all(content.as_bytes()));
+    match result {
+        Ok(_) => done(),
+        Err(e) => io_error("append", path, e),
+    }
+}
+
+// Archivos y directorios vacios; con `recursive` tambien directorios con contenido
+pub fn delete(path: &str, recursive: bool) -> VasoType {
+    let result = match fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() && recursive => fs::remove_dir_all(path),
+        Ok(meta) if meta.is_dir() => fs::remove_dir(path),
+        Ok(_) => fs::remove_file(path),
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(_) => done(),
+        Err(e) if e.kind() == ErrorKind::DirectoryNotEmpty => io_error("delete", path, "directory not empty (use {recursive: on})"),
+        Err(e) => io_error("delete", path, e),
+    }
+}
+
+pub fn copy(from: &str, to: &str) -> VasoType {
+    match fs::copy(from, to) {
+        Ok(_) => done(),
+        Err(e) => VasoType::VBit(3, format!("File.copy: '{}' -> '{}': {}", from, to, e)),
+    }
+}
+
+// rename no cruza discos: en ese caso copiamos y borramos
+pub fn rename(from: &str, to: &str) -> VasoType {
+    match fs::rename(from, to) {
+        Ok(_) => done(),
+        Err(e) if e.kind() == ErrorKind::CrossesDevices && Path::new(from).is_file() => {
+            match fs::copy(from, to).and_then(|_| fs::remove_file(from)) {
+                Ok(_) => done(),
+                Err(e) => io_error("move", from, e),
+            }
+        },
+        Err(e) => io_error("move", from, e),
+    }
+}
+
+// mkdir -p
+pub fn mkdir(path: &str) -> VasoType {
+    match fs::create_dir_all(path) {
+        Ok(_) => done(),
+        Err(e) => io_error("mkdir", path, e),
+    }
+}
+
+// Nombres (no rutas) ordenados
+pub fn list(dir: &str) -> VasoType {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => return io_error("list", dir, e),
+    };
+    let mut names = Vec::new();
+    for entry in entries {
+        match entry {
+            Ok(entry) => names.push(entry.file_name().to_string_lossy().to_string()),
+            Err(e) => return io_error("list", dir, e),
+        }
+    }
+    names.sort();
+    VasoType::List(names.into_iter().map(VasoType::Str).collect())
+}
+
+// "dist/**/*.js" -> rutas ordenadas. Un patron invalido es Error; sin coincidencias, lista vacia.
+pub fn glob(pattern: &str) -> VasoType {
+    let paths = match glob::glob(pattern) {
+        Ok(paths) => paths,
+        Err(e) => return VasoType::VBit(3, format!("File.glob: invalid pattern '{}': {}", pattern, e)),
+    };
+    let mut found = Vec::new();
+    for path in paths {
+        match path {
+            Ok(p) => found.push(p.to_string_lossy().to_string()),
+            Err(e) => return io_error("glob", &e.path().to_string_lossy(), e.error()),
+        }
+    }
+    found.sort();
+    VasoType::List(found.into_iter().map(VasoType::Str).collect())
+}
+
+// {size: Bytes, mtime: DateTime, is_dir: vbit, is_file: vbit}
+pub fn stat(path: &str) -> VasoType {
+    let meta = match fs::metadata(path) {
+        Ok(meta) => meta,
+        Err(e) => return io_error("stat", path, e),
+    };
+    let flag = |b: bool| VasoType::VBit(if b { 1 } else { 0 }, "".to_string());
+    let mut fields = BTreeMap::new();
+    fields.insert("size".to_string(), VasoType::Bytes(meta.len()));
+    fields.insert("is_dir".to_string(), flag(meta.is_dir()));
+    fields.insert("is_file".to_string(), flag(meta.is_file()));
+    let mtime = meta.modified().ok()
+        .map(|t| chrono::DateTime::<chrono::Utc>::from(t).fixed_offset())
+        .map(VasoType::DateTime)
+        .unwrap_or(VasoType::VBit(4, "mtime not available".to_string()));
+    fields.insert("mtime".to_string(), mtime);
+    VasoType::Map(fields)
+}
+
+pub fn read_lines(path: &str) -> VasoType {
+    match fs::read_to_string(path) {
+        Ok(content) => VasoType::List(content.lines().map(|l| VasoType::Str(l.to_string())).collect()),
+        Err(e) => io_error("read_lines", path, e),
+    }
+}
+
+// Temporal en el mismo directorio + fsync + rename: un lector ve el archivo viejo o el
+// nuevo completo, nunca uno a medias.
+pub fn write_atomic(path: &str, content: &str) -> VasoType {
+    let target = Path::new(path);
+    let dir = match target.parent() {
+        Some(d) if !d.as_os_str().is_empty() => d.to_path_buf(),
+        _ => Path::new(".").to_path_buf(),
+    };
+    let name = target.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let tmp = dir.join(format!(".{}.tmp-{}", name, std::process::id()));
+    let result = fs::File::create(&tmp)
+        .and_then(|mut file| { file.write_all(content.as_bytes())?; file.sync_all() })
+        .and_then(|_| fs::rename(&tmp, target));
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp);
+        return io_error("write_atomic", path, e);
+    }
+    // El rename vive en el directorio: tambien hay que persistirlo
+    #[cfg(unix)]
+    if let Ok(d) = fs::File::open(&dir) { let _ = d.sync_all(); }
+    done()
+}
@@ -0,0 +1,204 @@
+use crate::types::VasoType;
+use serde_json::{Map, Number, Value};
+use yaml_rust2::{ScanError, Yaml, YamlEmitter, YamlLoader};
+
+// --- YAML y TOML ---
+// Ambos se leen al mismo valor que Json (VasoType::Json), asi Json.get/set/validate
+// y los bucles for funcionan igual sobre manifests de Kubernetes o Cargo.toml.
+
+pub fn parse_error(kind: &str, msg: &str, line: usize, column: usize) -> String {
+    format!("{} Error (line {}, column {}): {}", kind, line, column, msg)
+}
+
+// Linea y columna (desde 1) de un offset en bytes
+fn line_col(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map(|l| l.chars().count()).unwrap_or(0) + 1;
+    (line, column)
+}
+
+// --- YAML ---
+
+fn yaml_error(e: ScanError) -> String {
+    let mark = e.marker();
+    parse_error("YAML", e.info(), mark.line(), mark.col() + 1)
+}
+
+fn yaml_key(key: Yaml) -> Result<String, String> {
+    match yaml_to_json(key)? {
+        Value::String(s) => Ok(s),
+        other => Ok(other.to_string()),
+    }
+}
+
+// Las etiquetas (!Ref foo) ya llegan descartadas desde el loader
+fn yaml_to_json(v: Yaml) -> Result<Value, String> {
+    Ok(match v {
+        Yaml::Null => Value::Null,
+        Yaml::Boolean(b) => Value::Bool(b),
+        Yaml::Integer(i) => Value::from(i),
+        Yaml::Real(text) => {
+            let f = Yaml::Real(text.clone()).as_f64().unwrap_or(f64::NAN);
+            Value::Number(Number::from_f64(f).ok_or_else(|| format!("YAML Error: {} has no JSON equivalent", text))?)
+        },
+        Yaml::String(s) => Value::String(s),
+        Yaml::Array(items) => Value::Array(items.into_iter().map(yaml_to_json).collect::<Result<_, _>>()?),
+        // Claves no textuales (1: a, true: b) se guardan como texto
+        Yaml::Hash(map) => {
+            let mut out = Map::new();
+            for (k, v) in map { out.insert(yaml_key(k)?, yaml_to_json(v)?); }
+            Value::Object(out)
+        },
+        // Un alias a un ancla inexistente o un `!!int abc`
+        Yaml::Alias(_) | Yaml::BadValue => return Err("YAML Error: invalid value".to_string()),
+    })
+}
+
+fn json_to_yaml(v: &Value) -> Yaml {
+    match v {
+        Value::Null => Yaml::Null,
+        Value::Bool(b) => Yaml::Boolean(*b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => Yaml::Integer(i),
+            None => Yaml::Real(n.to_string()),
+        },
+        Value::String(s) => Yaml::String(s.clone()),
+        Value::Array(items) => Yaml::Array(items.iter().map(json_to_yaml).collect()),
+        Value::Object(map) => Yaml::Hash(map.iter().map(|(k, v)| (Yaml::String(k.clone()), json_to_yaml(v))).collect()),
+    }
+}
+
+pub fn yaml_parse_all(text: &str) -> Result<Vec<Value>, String> {
+    let docs = YamlLoader::load_from_str(text).map_err(yaml_error)?;
+    docs.into_iter().map(yaml_to_json).collect()
+}
+
+// Un solo documento; un stream con varios ("---") pide Yaml.parse_all
+pub fn yaml_parse(text: &str) -> VasoType {
+    match yaml_parse_all(text) {
+        Ok(mut docs) if docs.len() <= 1 => VasoType::Json(docs.pop().unwrap_or(Value::Null)),
+        Ok(docs) => VasoType::VBit(3, format!("YAML has {} documents, use Yaml.parse_all", docs.len())),
+        Err(e) => VasoType::VBit(3, e),
+    }
+}
+
+pub fn yaml_stringify(docs: &[Value]) -> VasoType {
+    let mut out = String::new();
+    for (i, doc) in docs.iter().enumerate() {
+        let mut text = String::new();
+        if let Err(e) = YamlEmitter::new(&mut text).dump(&json_to_yaml(doc)) {
+            return VasoType::VBit(3, format!("YAML Error: {}", e));
+        }
+        // El emisor abre cada documento con "---"; el primero va sin separador
+        let body = text.strip_prefix("---\n").unwrap_or(&text);
+        if i > 0 { out.push_str("---\n"); }
+        out.push_str(body);
+        out.push('\n');
+    }
+    VasoType::Str(out)
+}
+
+// --- TOML ---
+
+fn toml_to_json(v: toml::Value) -> Result<Value, String> {
+    use toml::Value as T;
+    Ok(match v {
+        T::String(s) => Value::String(s),
+        T::Integer(i) => Value::from(i),
+        T::Float(f) => Value::Number(Number::from_f64(f).ok_or_else(|| format!("TOML Error: {} has no JSON equivalent", f))?),
+        T::Boolean(b) => Value::Bool(b),
+        // 1979-05-27T07:32:00Z -> texto (Time.parse lo entiende)
+        T::Datetime(dt) => Value::String(dt.to_string()),
+        T::Array(items) => Value::Array(items.into_iter().map(toml_to_json).collect::<Result<_, _>>()?),
+        T::Table(table) => {
+            let mut out = Map::new();
+            for (k, v) in table { out.insert(k, toml_to_json(v)?); }
+            Value::Object(out)
+        },
+    })
+}
+
+pub fn toml_parse(text: &str) -> VasoType {
+    match text.parse::<toml::Table>() {
+        Ok(table) => toml_to_json(toml::Value::Table(table)).map(VasoType::Json).unwrap_or_else(|e| VasoType::VBit(3, e)),
+        Err(e) => {
+            let msg = e.message().trim().to_string();
+            match e.span() {
+                Some(span) => {
+                    let (line, column) = line_col(text, span.start);
+                    // Algunos errores de toml solo traen la posicion
+                    let msg = match msg.as_str() {
+                        "" if span.start >= text.trim_end().len() => "unexpected end of input".to_string(),
+                        "" => "invalid syntax".to_string(),
+                        _ => msg,
+                    };
+                    VasoType::VBit(3, parse_error("TOML", &msg, line, column))
+                },
+                None => VasoType::VBit(3, format!("TOML Error: {}", msg)),
+            }
+        }
+    }
+}
+
+// Primer null del documento, como ruta: "deps.serde.version"
+fn find_null(v: &Value, path: &str) -> Option<String> {
+    let nested = |key: String| if path.is_empty() { key } else { format!("{}.{}", path, key) };
+    match v {
+        Value::Null => Some(path.to_string()),
+        Value::Array(items) => items.iter().enumerate().find_map(|(i, item)| find_null(item, &format!("{}[{}]", path, i))),
+        Value::Object(map) => map.iter().find_map(|(k, item)| find_null(item, &nested(k.clone()))),
+        _ => None,
+    }
+}
+
+// TOML no tiene null y la raiz tiene que ser una tabla
+pub fn toml_stringify(doc: &Value) -> VasoType {
+    if !doc.is_object() {
+        return VasoType::VBit(3, "TOML Error: the top level must be a map".to_string());
+    }
+    if let Some(path) = find_null(doc, "") {
+        return VasoType::VBit(3, format!("TOML Error: '{}' is unknown/null, which TOML cannot represent", path));
+    }
+    match toml::to_string(doc) {
+        Ok(text) => VasoType::Str(text),
+        Err(e) => VasoType::VBit(3, format!("TOML Error: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn yaml_reads_into_json_values() {
+        let doc = yaml_parse("kind: Deployment\nspec:\n  replicas: 3\n  ports: [80, 443]\n1: one\n");
+        assert_eq!(doc, VasoType::Json(json!({"kind": "Deployment", "spec": {"replicas": 3, "ports": [80, 443]}, "1": "one"})));
+        assert_eq!(yaml_parse_all("a: 1\n---\na: 2\n").unwrap(), vec![json!({"a": 1}), json!({"a": 2})]);
+        assert_eq!(yaml_parse("a: 1\n---\na: 2\n"), VasoType::VBit(3, "YAML has 2 documents, use Yaml.parse_all".to_string()));
+    }
+
+    #[test]
+    fn yaml_errors_carry_the_position() {
+        let VasoType::VBit(3, msg) = yaml_parse("a: b\n  c: d\n") else { panic!() };
+        assert!(msg.starts_with("YAML Error (line 2, column 4): "), "{}", msg);
+    }
+
+    #[test]
+    fn yaml_stringify_separates_documents() {
+        let VasoType::Str(text) = yaml_stringify(&[json!({"a": 1}), json!(["x"])]) else { panic!() };
+        assert_eq!(text, "a: 1\n---\n- x\n");
+    }
+
+    #[test]
+    fn toml_round_trip_and_null() {
+        let VasoType::Json(doc) = toml_parse("[package]\nname = \"vaso\"\nreleased = 1979-05-27T07:32:00Z\n") else { panic!() };
+        assert_eq!(doc, json!({"package": {"name": "vaso", "released": "1979-05-27T07:32:00Z"}}));
+        assert_eq!(toml_stringify(&json!({"a": 1})), VasoType::Str("a = 1\n".to_string()));
+        assert_eq!(
+            toml_stringify(&json!({"deps": {"serde": {"version": null}}})),
+            VasoType::VBit(3, "TOML Error: 'deps.serde.version' is unknown/null, which TOML cannot represent".to_string())
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use crate::types::VasoType;
+use serde_json::{Map, Number, Value};
+use std::collections::BTreeMap;
+
+// --- JSON: rutas (spec.containers[0].image), Json.set y conversion a valores nativos ---
+// Correspondencia nativa:
+//   null <-> unknown, true/false <-> on/off, enteros de 32 bits <-> Int, decimales <-> Float,
+//   string <-> Str, array <-> List, object <-> Map.
+// Un entero que no cabe en Int se queda como Json para no perder precision.
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(i64),
+}
+
+// "spec.containers[0].image", "$.items[-1]", "labels[\"app.kubernetes.io/name\"]"
+fn parse_path(path: &str) -> Result<Vec<Segment>, String> {
+    let bad = |why: &str| format!("Invalid JSON path '{}': {}", path, why);
+    let path = path.strip_prefix('$').unwrap_or(path);
+    let chars: Vec<char> = path.chars().collect();
+    let mut segments = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '.' => {
+                if i + 1 == chars.len() || matches!(chars[i + 1], '.' | '[') { return Err(bad("empty key")); }
+                i += 1;
+            },
+            '[' => {
+                let close = chars[i..].iter().position(|c| *c == ']').map(|p| p + i)
+                    .ok_or_else(|| bad("missing ']'"))?;
+                let inner: String = chars[i + 1..close].iter().collect();
+                let inner = inner.trim();
+                if let Some(key) = inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+                    segments.push(Segment::Key(key.to_string()));
+                } else {
+                    let index = inner.parse::<i64>().map_err(|_| bad(&format!("'{}' is not an index", inner)))?;
+                    segments.push(Segment::Index(index));
+                }
+                i = close + 1;
+            },
+            _ => {
+                let end = chars[i..].iter().position(|c| matches!(c, '.' | '[')).map(|p| p + i).unwrap_or(chars.len());
+                segments.push(Segment::Key(chars[i..end].iter().collect()));
+                i = end;
+            }
+        }
+    }
+    Ok(segments)
+}
+
+// Indices negativos cuentan desde el final: items[-1]
+fn resolve_index(index: i64, len: usize) -> Option<usize> {
+    let idx = if index < 0 { len as i64 + index } else { index };
+    if idx >= 0 { Some(idx as usize) } else { None }
+}
+
+fn describe(segments: &[Segment]) -> String {
+    let mut out = String::new();
+    for seg in segments {
+        match seg {
+            Segment::Key(k) if out.is_empty() => out.push_str(k),
+            Segment::Key(k) => { out.push('.'); out.push_str(k); },
+            Segment::Index(n) => out.push_str(&format!("[{}]", n)),
+        }
+    }
+    out
+}
+
+// Json.get(doc, "spec.containers[0].image"). Un camino inexistente es un error, igual que
+// doc.spec.containers con un campo que falta (un `null` presente si es unknown).
+pub fn get(doc: &Value, path: &str) -> VasoType {
+    let segments = match parse_path(path) {
+        Ok(s) => s,
+        Err(e) => return VasoType::VBit(3, e),
+    };
+    let mut current = doc;
+    for (n, seg) in segments.iter().enumerate() {
+        let next = match (seg, current) {
+            (Segment::Key(k), Value::Object(map)) => map.get(k),
+            (Segment::Index(idx), Value::Array(items)) => resolve_index(*idx, items.len()).and_then(|i| items.get(i)),
+            _ => None,
+        };
+        match next {
+            Some(v) => current = v,
+            None => return VasoType::VBit(3, format!("Field '{}' not found", describe(&segments[..=n]))),
+        }
+    }
+    to_native(current)
+}
+
+// Json.set(doc, "metadata.labels.env", "prod"): crea los objetos intermedios que falten;
+// en un array solo se puede reemplazar o anadir al final (items[len]).
+pub fn set(doc: &mut Value, path: &str, value: Value) -> Result<(), String> {
+    let segments = parse_path(path)?;
+    let mut current = doc;
+    for (n, seg) in segments.iter().enumerate() {
+        let here = describe(&segments[..n]);
+        let here = if here.is_empty() { "$".to_string() } else { here };
+        current = match seg {
+            Segment::Key(k) => {
+                if current.is_null() { *current = Value::Object(Map::new()); }
+                match current {
+                    Value::Object(map) => map.entry(k.clone()).or_insert(Value::Null),
+                    other => return Err(format!("Cannot set '{}': '{}' is {}, not an object", path, here, kind(other))),
+                }
+            },
+            Segment::Index(idx) => match current {
+                Value::Array(items) => {
+                    let len = items.len();
+                    match resolve_index(*idx, len) {
+                        Some(i) if i < len => &mut items[i],
+                        Some(i) if i == len => { items.push(Value::Null); &mut items[i] },
+                        _ => return Err(format!("Cannot set '{}': index {} out of range (len {})", path, idx, len)),
+                    }
+                },
+                other => return Err(format!("Cannot set '{}': '{}' is {}, not an array", path, here, kind(other))),
+            },
+        };
+    }
+    *current = value;
+    Ok(())
+}
+
+fn kind(v: &Value) -> &'static str {
+    match v {
+        Value::Null => "null",
+        Value::Bool(_) => "a bool",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+// Json.parse: los errores llevan linea y columna, como Yaml y Toml
+pub fn parse(text: &str) -> VasoType {
+    match serde_json::from_str::<Value>(text) {
+        Ok(v) => VasoType::Json(v),
+        Err(e) => {
+            let msg = e.to_string();
+            let msg = msg.split(" at line ").next().unwrap_or(&msg);
+            VasoType::VBit(3, crate::formats::parse_error("JSON", msg, e.line(), e.column()))
+        }
+    }
+}
+
+pub fn to_native(v: &Value) -> VasoType {
+    match v {
+        Value::Null => VasoType::VBit(4, "".to_string()),
+        Value::Bool(b) => crate::logic::bool_vbit(*b),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64().and_then(|i| i32::try_from(i).ok()) { VasoType::Int(i) }
+            else if n.is_f64() { VasoType::Float(n.as_f64().unwrap_or(f64::NAN)) }
+            else { VasoType::Json(v.clone()) }
+        },
+        Value::String(s) => VasoType::Str(s.clone()),
+        Value::Array(items) => VasoType::List(items.iter().map(to_native).collect()),
+        Value::Object(map) => VasoType::Map(map.iter().map(|(k, v)| (k.clone(), to_native(v))).collect::<BTreeMap<_, _>>()),
+    }
+}
+
+// Duraciones, tamanos y fechas se escriben como su texto ("1m30s", "10MB", RFC 3339).
+// loading, error o un unknown con mensaje no tienen equivalente JSON.
+pub fn from_native(v: &VasoType) -> Result<Value, String> {
+    Ok(match v {
+        VasoType::Json(v) => v.clone(),
+        VasoType::Int(n) => Value::from(*n),
+        VasoType::Float(f) => Number::from_f64(*f).map(Value::Number)
+            .ok_or_else(|| format!("{} has no JSON representation", f))?,
+        VasoType::Str(s) => Value::String(s.clone()),
+        VasoType::VBit(1, _) => Value::Bool(true),
+        VasoType::VBit(0, _) => Value::Bool(false),
+        VasoType::VBit(4, msg) if msg.is_empty() => Value::Null,
+        VasoType::VBit(3, msg) => return Err(msg.clone()),
+        VasoType::List(items) => Value::Array(items.iter().map(from_native).collect::<Result<_, _>>()?),
+        VasoType::Map(fields) => {
+            let mut map = Map::new();
+            for (k, v) in fields { map.insert(k.clone(), from_native(v)?); }
+            Value::Object(map)
+        },
+        VasoType::Duration(_) | VasoType::Bytes(_) | VasoType::DateTime(_) => Value::String(v.to_string()),
+        other => return Err(format!("{} has no JSON representation", other.render())),
+    })
+}
+
+pub fn stringify(v: &VasoType, pretty: bool) -> VasoType {
+    let value = match from_native(v) {
+        Ok(value) => value,
+        Err(e) => return VasoType::VBit(3, e),
+    };
+    let text = if pretty { serde_json::to_string_pretty(&value) } else { serde_json::to_string(&value) };
+    match text {
+        Ok(s) => VasoType::Str(s),
+        Err(e) => VasoType::VBit(3, format!("JSON Error: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn key(k: &str) -> Segment {
+        Segment::Key(k.to_string())
+    }
+
+    #[test]
+    fn parses_paths() {
+        assert_eq!(parse_path("spec.containers[0].image").unwrap(), vec![key("spec"), key("containers"), Segment::Index(0), key("image")]);
+        assert_eq!(parse_path("$.items[-1]").unwrap(), vec![key("items"), Segment::Index(-1)]);
+        assert_eq!(parse_path("labels[\"app.kubernetes.io/name\"]").unwrap(), vec![key("labels"), key("app.kubernetes.io/name")]);
+        assert_eq!(parse_path("$").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn rejects_bad_paths() {
+        assert_eq!(parse_path("a..b").unwrap_err(), "Invalid JSON path 'a..b': empty key");
+        assert_eq!(parse_path("a.").unwrap_err(), "Invalid JSON path 'a.': empty key");
+        assert_eq!(parse_path("items[0").unwrap_err(), "Invalid JSON path 'items[0': missing ']'");
+        assert_eq!(parse_path("items[x]").unwrap_err(), "Invalid JSON path 'items[x]': 'x' is not an index");
+    }
+
+    #[test]
+    fn set_creates_objects_and_appends() {
+        let mut doc = json!({"items": [1, 2]});
+        set(&mut doc, "metadata.labels.env", json!("prod")).unwrap();
+        set(&mut doc, "items[-1]", json!(20)).unwrap();
+        set(&mut doc, "items[2]", json!(3)).unwrap();
+        assert_eq!(doc, json!({"items": [1, 20, 3], "metadata": {"labels": {"env": "prod"}}}));
+    }
+
+    #[test]
+    fn set_reports_where_it_stopped() {
+        let mut doc = json!({"items": [1], "name": "api"});
+        assert_eq!(set(&mut doc, "items[5]", json!(0)).unwrap_err(), "Cannot set 'items[5]': index 5 out of range (len 1)");
+        assert_eq!(set(&mut doc, "name.first", json!(0)).unwrap_err(), "Cannot set 'name.first': 'name' is a string, not an object");
+        assert_eq!(set(&mut doc, "items.x", json!(0)).unwrap_err(), "Cannot set 'items.x': 'items' is an array, not an object");
+        assert_eq!(doc, json!({"items": [1], "name": "api"}));
+    }
+
+    #[test]
+    fn get_missing_path_is_an_error() {
+        let doc = json!({"spec": {"containers": [{"image": "nginx"}]}});
+        assert_eq!(get(&doc, "spec.containers[-1].image"), VasoType::Str("nginx".to_string()));
+        assert_eq!(get(&doc, "spec.containers[3]"), VasoType::VBit(3, "Field 'spec.containers[3]' not found".to_string()));
+    }
+}
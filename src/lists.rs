@@ -0,0 +1,255 @@
+use crate::types::{VasoType, vbit_name};
+use crate::logic::{apply_op, bool_vbit, get_vbit_priority};
+use crate::parser::get_field;
+use crate::tokens::Token;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+// --- LISTAS: List.sort / unique / zip / sum / partition_by_state ... (y metodos: pods.len()) ---
+// Las listas son valores: push, pop, insert, remove, sort y reverse devuelven una lista nueva.
+// Como sentencia sobre una variable (`queue.push(job);`) el interprete guarda el resultado.
+
+// Operaciones que, usadas como sentencia, reescriben la variable
+pub const IN_PLACE: [&str; 6] = ["push", "pop", "insert", "remove", "sort", "reverse"];
+
+fn err(msg: String) -> VasoType {
+    VasoType::VBit(3, msg)
+}
+
+// Indice con negativos desde el final; None si queda fuera de [0, len)
+fn index(i: i32, len: usize) -> Option<usize> {
+    let idx = if i < 0 { len as i64 + i as i64 } else { i as i64 };
+    if idx >= 0 && (idx as usize) < len { Some(idx as usize) } else { None }
+}
+
+fn as_f64(v: &VasoType) -> Option<f64> {
+    match v {
+        VasoType::Int(n) => Some(*n as f64),
+        VasoType::Float(x) => Some(*x),
+        _ => None,
+    }
+}
+
+// Orden natural: numeros, textos, unidades y fechas; los V-Bits por dominancia (off < on < ... < error)
+fn compare(a: &VasoType, b: &VasoType) -> Result<Ordering, String> {
+    use VasoType::*;
+    let ord = match (a, b) {
+        (Str(x), Str(y)) => Some(x.cmp(y)),
+        (Duration(x), Duration(y)) | (Bytes(x), Bytes(y)) => Some(x.cmp(y)),
+        (DateTime(x), DateTime(y)) => Some(x.cmp(y)),
+        (VBit(x, _), VBit(y, _)) => Some(get_vbit_priority(*x).cmp(&get_vbit_priority(*y))),
+        _ => match (as_f64(a), as_f64(b)) {
+            (Some(x), Some(y)) => x.partial_cmp(&y),
+            _ => None,
+        },
+    };
+    ord.ok_or_else(|| format!("cannot compare {} with {}", a.render(), b.render()))
+}
+
+// Clave de orden: un campo ("restarts") o una ruta ("status.code") de cada elemento
+fn key_of(item: &VasoType, key: &str) -> VasoType {
+    key.split('.').fold(item.clone(), |val, field| get_field(&val, field))
+}
+
+fn sort(items: &[VasoType], key: Option<&str>) -> VasoType {
+    let keyed: Vec<(VasoType, &VasoType)> = items.iter()
+        .map(|item| (key.map(|k| key_of(item, k)).unwrap_or_else(|| item.clone()), item))
+        .collect();
+    if let Some((VasoType::VBit(3, msg), _)) = keyed.iter().find(|(k, _)| key.is_some() && matches!(k, VasoType::VBit(3, _))) {
+        return err(format!("List.sort: {}", msg));
+    }
+    let mut failure = None;
+    let mut sorted = keyed;
+    // sort_by es estable: elementos iguales conservan su orden
+    sorted.sort_by(|(a, _), (b, _)| compare(a, b).unwrap_or_else(|e| { failure.get_or_insert(e); Ordering::Equal }));
+    match failure {
+        Some(e) => err(format!("List.sort: {}", e)),
+        None => VasoType::List(sorted.into_iter().map(|(_, item)| item.clone()).collect()),
+    }
+}
+
+// V-Bit dominante de la lista (error > unknown > loading > on > off), si hay alguno
+fn dominant_state(items: &[VasoType]) -> Option<VasoType> {
+    items.iter().filter(|v| matches!(v, VasoType::VBit(..)))
+        .cloned()
+        .reduce(|acc, v| apply_op(acc, &v, &Token::Plus))
+}
+
+// min/max: un V-Bit en la lista "infecta" el resultado, igual que en una suma
+fn extreme(items: &[VasoType], want: Ordering, name: &str) -> VasoType {
+    if let Some(state) = dominant_state(items) { return state; }
+    let mut best: Option<&VasoType> = None;
+    for item in items {
+        best = match best {
+            None => Some(item),
+            Some(current) => match compare(item, current) {
+                Ok(ord) if ord == want => Some(item),
+                Ok(_) => Some(current),
+                Err(e) => return err(format!("List.{}: {}", name, e)),
+            },
+        };
+    }
+    best.cloned().unwrap_or_else(|| err(format!("List.{} of an empty list", name)))
+}
+
+fn sum(items: &[VasoType]) -> VasoType {
+    let mut iter = items.iter();
+    match iter.next() {
+        None => VasoType::Int(0),
+        Some(first) => iter.fold(first.clone(), |acc, v| apply_op(acc, v, &Token::Plus)),
+    }
+}
+
+fn mean(items: &[VasoType]) -> VasoType {
+    if items.is_empty() { return err("List.mean of an empty list".to_string()); }
+    match sum(items) {
+        VasoType::Int(total) => VasoType::Float(total as f64 / items.len() as f64),
+        VasoType::Float(total) => VasoType::Float(total / items.len() as f64),
+        VasoType::Duration(ms) => VasoType::Duration(ms / items.len() as u64),
+        VasoType::Bytes(b) => VasoType::Bytes(b / items.len() as u64),
+        state @ VasoType::VBit(..) => state,
+        other => err(format!("List.mean: cannot average {}", other.render())),
+    }
+}
+
+fn unique(items: &[VasoType]) -> VasoType {
+    let mut seen: Vec<VasoType> = Vec::new();
+    for item in items {
+        if !seen.contains(item) { seen.push(item.clone()); }
+    }
+    VasoType::List(seen)
+}
+
+// {on: [...], off: [...], loading: [...], error: [...], unknown: [...]}
+// Un registro de Sys.exec cuenta por su status; un valor sin estado va a unknown.
+fn partition_by_state(items: &[VasoType]) -> VasoType {
+    let mut buckets: BTreeMap<String, VasoType> = (0..=4).map(|lvl| (vbit_name(lvl).to_string(), VasoType::List(Vec::new()))).collect();
+    for item in items {
+        let bucket = match item.state() {
+            VasoType::VBit(lvl, _) => vbit_name(*lvl),
+            _ => "unknown",
+        };
+        if let Some(VasoType::List(list)) = buckets.get_mut(bucket) { list.push(item.clone()); }
+    }
+    VasoType::Map(buckets)
+}
+
+// Un VBit se busca por su estado: xs.contains(error) encuentra error("timeout")
+fn same(a: &VasoType, b: &VasoType) -> bool {
+    match (a, b) {
+        (VasoType::VBit(x, _), VasoType::VBit(y, _)) => x == y,
+        _ => a == b,
+    }
+}
+
+pub fn call(func: &str, args: &[VasoType]) -> VasoType {
+    use VasoType::{Int, List, Str};
+    // `error` es un elemento mas en push/insert/contains/index_of; en cualquier otro
+    // argumento (la lista, un indice) se propaga como en el resto de la stdlib
+    let element = match func {
+        "push" | "contains" | "index_of" => Some(1),
+        "insert" => Some(2),
+        _ => None,
+    };
+    let failed = args.iter().enumerate().find(|(pos, a)| Some(*pos) != element && matches!(a, VasoType::VBit(3, _)));
+    if let Some((_, e)) = failed { return e.clone(); }
+    let Some(List(items)) = args.first() else {
+        return err(format!("List.{} needs a List, got {:?}", func, args.first()));
+    };
+    let rest = &args[1..];
+    match (func, rest) {
+        ("len", []) => Int(items.len() as i32),
+        ("get", [Int(i)]) => index(*i, items.len()).map(|i| items[i].clone())
+            .unwrap_or_else(|| VasoType::VBit(4, format!("Index {} out of range (len {})", i, items.len()))),
+        ("push", [value]) => {
+            let mut out = items.clone();
+            out.push(value.clone());
+            List(out)
+        },
+        ("pop", []) => match items.split_last() {
+            Some((_, rest)) => List(rest.to_vec()),
+            None => err("List.pop on an empty list".to_string()),
+        },
+        ("insert", [Int(i), value]) => {
+            let pos = if *i < 0 { items.len() as i64 + *i as i64 } else { *i as i64 };
+            if pos < 0 || pos > items.len() as i64 {
+                return err(format!("List.insert: index {} out of range (len {})", i, items.len()));
+            }
+            let mut out = items.clone();
+            out.insert(pos as usize, value.clone());
+            List(out)
+        },
+        ("remove", [Int(i)]) => match index(*i, items.len()) {
+            Some(pos) => {
+                let mut out = items.clone();
+                out.remove(pos);
+                List(out)
+            },
+            None => err(format!("List.remove: index {} out of range (len {})", i, items.len())),
+        },
+        ("contains", [value]) => bool_vbit(items.iter().any(|v| same(v, value))),
+        ("index_of", [value]) => match items.iter().position(|v| same(v, value)) {
+            Some(pos) => Int(pos as i32),
+            None => VasoType::VBit(4, format!("{} not in list", value.render())),
+        },
+        ("sort", []) => sort(items, None),
+        ("sort", [Str(key)]) => sort(items, Some(key)),
+        ("reverse", []) => List(items.iter().rev().cloned().collect()),
+        ("unique", []) => unique(items),
+        ("zip", [List(other)]) => List(items.iter().zip(other).map(|(a, b)| List(vec![a.clone(), b.clone()])).collect()),
+        ("chunk", [Int(n)]) if *n > 0 => List(items.chunks(*n as usize).map(|c| List(c.to_vec())).collect()),
+        // Un solo nivel: [[1, 2], 3] -> [1, 2, 3]
+        ("flatten", []) => List(items.iter().flat_map(|v| match v {
+            List(inner) => inner.clone(),
+            other => vec![other.clone()],
+        }).collect()),
+        ("sum", []) => sum(items),
+        ("min", []) => extreme(items, Ordering::Less, "min"),
+        ("max", []) => extreme(items, Ordering::Greater, "max"),
+        ("mean", []) => mean(items),
+        ("partition_by_state", []) => partition_by_state(items),
+        (
+            "len" | "get" | "push" | "pop" | "insert" | "remove" | "contains" | "index_of" | "sort" | "reverse"
+            | "unique" | "zip" | "chunk" | "flatten" | "sum" | "min" | "max" | "mean" | "partition_by_state",
+            _,
+        ) => err(format!("List.{} got invalid args {:?}", func, rest)),
+        _ => err(format!("List.{} not found", func)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ints(xs: &[i32]) -> VasoType { VasoType::List(xs.iter().map(|n| VasoType::Int(*n)).collect()) }
+
+    #[test]
+    fn negative_indexes_count_from_the_end() {
+        assert_eq!(call("get", &[ints(&[1, 2, 3]), VasoType::Int(-1)]), VasoType::Int(3));
+        assert_eq!(call("insert", &[ints(&[1, 3]), VasoType::Int(-1), VasoType::Int(2)]), ints(&[1, 2, 3]));
+        assert_eq!(call("remove", &[ints(&[1, 2]), VasoType::Int(5)]), VasoType::VBit(3, "List.remove: index 5 out of range (len 2)".to_string()));
+    }
+
+    #[test]
+    fn sort_by_field_is_stable_and_rejects_mixed_types() {
+        let pod = |name: &str, restarts: i32| VasoType::Map([
+            ("name".to_string(), VasoType::Str(name.to_string())),
+            ("restarts".to_string(), VasoType::Int(restarts)),
+        ].into_iter().collect());
+        let sorted = call("sort", &[VasoType::List(vec![pod("b", 2), pod("a", 1), pod("c", 1)]), VasoType::Str("restarts".to_string())]);
+        assert_eq!(sorted, VasoType::List(vec![pod("a", 1), pod("c", 1), pod("b", 2)]));
+        assert!(matches!(call("sort", &[VasoType::List(vec![VasoType::Int(1), VasoType::Str("x".to_string())])]), VasoType::VBit(3, _)));
+    }
+
+    #[test]
+    fn partition_by_state_always_has_every_bucket() {
+        let parts = call("partition_by_state", &[VasoType::List(vec![VasoType::VBit(1, String::new()), VasoType::Int(7)])]);
+        match parts {
+            VasoType::Map(fields) => {
+                assert_eq!(fields.keys().map(String::as_str).collect::<Vec<_>>(), ["error", "loading", "off", "on", "unknown"]);
+                assert_eq!(fields["unknown"], ints(&[7]));
+            },
+            other => panic!("expected a map, got {:?}", other),
+        }
+    }
+}
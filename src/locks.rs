@@ -0,0 +1,177 @@
+use crate::resilience::{deadline_expired, format_millis};
+use crate::stdlib::sleep_ms;
+use crate::types::VasoType;
+use std::cell::RefCell;
+use std::fs::{self, File, TryLockError};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+// --- LOCKS ASESORES (Lock.acquire / with_lock path { }) ---
+// El lock es un lock del sistema (flock / LockFileEx) sobre el archivo, con el PID dentro
+// para los mensajes. El kernel lo suelta si el proceso muere (crash, kill -9), asi que un
+// lock huerfano se toma sin adivinar por el PID: dos ejecuciones nunca lo tienen a la vez.
+
+thread_local! {
+    static HELD: RefCell<Vec<(PathBuf, File)>> = const { RefCell::new(Vec::new()) };
+}
+
+// Espera como mucho `timeout_ms` (None = sin limite, salvo un timeout activo)
+pub fn acquire(path: &str, timeout_ms: Option<u64>) -> VasoType {
+    let lock = PathBuf::from(path);
+    if HELD.with(|h| h.borrow().iter().any(|(p, _)| *p == lock)) {
+        return VasoType::VBit(3, format!("Lock '{}' is already held by this script", path));
+    }
+    let start = Instant::now();
+    loop {
+        match try_take(&lock) {
+            Ok(Some(file)) => {
+                HELD.with(|h| h.borrow_mut().push((lock, file)));
+                return VasoType::VBit(1, "".to_string());
+            },
+            Ok(None) => {},
+            Err(e) => return VasoType::VBit(3, format!("Lock '{}': {}", path, e)),
+        }
+        let waited = start.elapsed().as_millis() as u64;
+        // Con timeout el mensaje da el limite pedido ("waited 30s"), no el retraso del ultimo sondeo
+        let gave_up = match timeout_ms {
+            Some(ms) if waited >= ms => Some(ms),
+            _ if deadline_expired() => Some(waited),
+            _ => None,
+        };
+        if let Some(waited) = gave_up {
+            let holder = owner_pid(&lock).map(|pid| format!(" by PID {}", pid)).unwrap_or_default();
+            return VasoType::VBit(3, format!("Lock '{}' held{} (waited {})", path, holder, format_millis(waited)));
+        }
+        sleep_ms(timeout_ms.map_or(100, |ms| (ms - waited).min(100)));
+    }
+}
+
+pub fn release(path: &str) -> VasoType {
+    let lock = PathBuf::from(path);
+    let held = HELD.with(|h| {
+        let mut held = h.borrow_mut();
+        let pos = held.iter().position(|(p, _)| *p == lock);
+        pos.map(|p| held.remove(p))
+    });
+    let Some((lock, file)) = held else {
+        return VasoType::VBit(3, format!("Lock '{}' is not held by this script", path));
+    };
+    // Se borra antes de soltarlo: quien espere sobre este archivo lo detecta en try_take
+    let removed = fs::remove_file(&lock);
+    drop(file);
+    match removed {
+        Ok(_) => VasoType::VBit(1, "".to_string()),
+        Err(e) => VasoType::VBit(3, format!("Lock '{}': {}", path, e)),
+    }
+}
+
+// Al terminar el script no dejamos locks colgados
+pub fn release_all() {
+    let held = HELD.with(|h| std::mem::take(&mut *h.borrow_mut()));
+    for (lock, file) in held {
+        let _ = fs::remove_file(lock);
+        drop(file);
+    }
+}
+
+// Some(archivo bloqueado) si el lock es nuestro; None si otro lo tiene
+fn try_take(lock: &Path) -> io::Result<Option<File>> {
+    loop {
+        let mut file = fs::OpenOptions::new().read(true).write(true).create(true).truncate(false).open(lock)?;
+        match file.try_lock() {
+            Ok(()) => {},
+            Err(TryLockError::WouldBlock) => return Ok(None),
+            Err(TryLockError::Error(e)) => return Err(e),
+        }
+        // Entre open y try_lock el duenio anterior pudo soltarlo y borrar el archivo:
+        // tendriamos un lock sobre un archivo que ya no esta en `lock`. Otra vuelta.
+        if !same_file(&file, lock) { continue; }
+        file.set_len(0)?;
+        writeln!(file, "{}", std::process::id())?;
+        file.sync_all()?;
+        return Ok(Some(file));
+    }
+}
+
+#[cfg(unix)]
+fn same_file(file: &File, lock: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (file.metadata(), fs::metadata(lock)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+// En Windows un archivo abierto no se puede reemplazar por otro con el mismo nombre
+#[cfg(not(unix))]
+fn same_file(_file: &File, lock: &Path) -> bool {
+    lock.exists()
+}
+
+// Solo para el mensaje: quien lo tiene segun el archivo
+fn owner_pid(lock: &Path) -> Option<u32> {
+    fs::read_to_string(lock).ok()?.lines().next()?.trim().parse().ok().filter(|pid| *pid > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Barrier};
+
+    fn lock_path(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("vaso-lock-{}-{}", std::process::id(), name));
+        let _ = fs::remove_file(&path);
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn stale_lock_file_is_taken_over() {
+        let path = lock_path("stale");
+        // Lo que deja un proceso muerto: el archivo con su PID, sin lock del sistema
+        fs::write(&path, "4000000\n").unwrap();
+        assert_eq!(acquire(&path, Some(0)), VasoType::VBit(1, "".to_string()));
+        assert_eq!(owner_pid(Path::new(&path)), Some(std::process::id()));
+        assert_eq!(release(&path), VasoType::VBit(1, "".to_string()));
+        assert!(!Path::new(&path).exists());
+    }
+
+    #[test]
+    fn only_one_run_takes_a_stale_lock() {
+        let path = lock_path("race");
+        fs::write(&path, "4000000\n").unwrap();
+        // Cada hilo tiene su propio HELD: se comportan como ejecuciones distintas
+        let barrier = Arc::new(Barrier::new(8));
+        let (tx, rx) = std::sync::mpsc::channel();
+        let threads: Vec<_> = (0..8).map(|_| {
+            let (path, barrier, tx) = (path.clone(), barrier.clone(), tx.clone());
+            std::thread::spawn(move || {
+                barrier.wait();
+                let got = acquire(&path, Some(0)) == VasoType::VBit(1, "".to_string());
+                tx.send(got).unwrap();
+                // Mantener el lock hasta que todos hayan probado
+                barrier.wait();
+                if got { release(&path); }
+            })
+        }).collect();
+        drop(tx);
+        let winners = rx.iter().take(8).filter(|got| *got).count();
+        for t in threads { t.join().unwrap(); }
+        assert_eq!(winners, 1);
+    }
+
+    #[test]
+    fn held_lock_reports_its_owner() {
+        let path = lock_path("held");
+        assert_eq!(acquire(&path, Some(0)), VasoType::VBit(1, "".to_string()));
+        let other = std::thread::spawn({
+            let path = path.clone();
+            move || acquire(&path, Some(0))
+        }).join().unwrap();
+        match other {
+            VasoType::VBit(3, msg) => assert!(msg.contains(&format!("by PID {}", std::process::id())), "{}", msg),
+            v => panic!("expected an error, got {:?}", v),
+        }
+        release(&path);
+    }
+}
@@ -1,8 +1,9 @@
 use crate::types::VasoType;
 use crate::tokens::Token;
+use std::cell::RefCell;
 
 // --- FIX 1: Restauramos la función que pide stdlib.rs ---
-pub fn check_errors(args: &Vec<VasoType>) -> Option<VasoType> {
+pub fn check_errors(args: &[VasoType]) -> Option<VasoType> {
     for arg in args {
         if let VasoType::VBit(3, msg) = arg {
             return Some(VasoType::VBit(3, msg.clone()));
@@ -11,19 +12,106 @@ pub fn check_errors(args: &Vec<VasoType>) -> Option<VasoType> {
     None
 }
 
+// --- POLITICA DE ESTADOS: `policy error > loading > unknown > on > off absorbing off;` ---
+// El orden (de mayor a menor) decide quien domina en `+`, VBit.worst/best y los warnings de
+// match. Con `off` absorbente, un off gana cualquier combinacion (una etapa apagada apaga el
+// total), pero sigue en su puesto para ordenar (List.sort).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Policy { pub order: [u8; 5], pub off_absorbing: bool }
+
+pub const DEFAULT_POLICY: Policy = Policy { order: [3, 4, 2, 1, 0], off_absorbing: false };
+
+thread_local! {
+    static POLICY: RefCell<Policy> = const { RefCell::new(DEFAULT_POLICY) };
+}
+
+pub fn policy() -> Policy {
+    POLICY.with(|p| p.borrow().clone())
+}
+
+// Devuelve la politica anterior para restaurarla al cerrar un bloque `policy ... { }`
+pub fn set_policy(policy: Policy) -> Policy {
+    POLICY.with(|p| p.replace(policy))
+}
+
 // --- FIX 2: Cambiamos i32 a u8 para coincidir con la definición de VBit ---
-fn get_vbit_priority(level: u8) -> i32 {
-    match level {
-        3 => 100, // Error
-        4 => 50,  // Unknown
-        2 => 25,  // Loading
-        1 => 10,  // On
-        0 => 0,   // Off
-        _ => -1
+// Por defecto: Error 100, Unknown 50, Loading 25, On 10, Off 0
+pub fn get_vbit_priority(level: u8) -> i32 {
+    const RANKS: [i32; 5] = [100, 50, 25, 10, 0];
+    POLICY.with(|p| p.borrow().order.iter().position(|l| *l == level).map(|pos| RANKS[pos]).unwrap_or(-1))
+}
+
+// ¿`a` gana a `b` al combinarlos? En empate gana `a` (el de la izquierda)
+pub fn dominates(a: u8, b: u8) -> bool {
+    if (a == 0 || b == 0) && POLICY.with(|p| p.borrow().off_absorbing) { return a == 0; }
+    get_vbit_priority(a) >= get_vbit_priority(b)
+}
+
+// Un match debe tener brazo para los estados que dominan a `on` (y para off si es absorbente)
+pub fn needs_arm(level: u8) -> bool {
+    level != 1 && !dominates(1, level)
+}
+
+pub fn bool_vbit(b: bool) -> VasoType {
+    if b { VasoType::VBit(1, "".to_string()) } else { VasoType::VBit(0, "".to_string()) }
+}
+
+// Duration + Duration, Bytes - Bytes, 10MB / 512KB (-> Int), comparaciones
+fn unit_op(a: u64, b: u64, op: &Token, wrap: fn(u64) -> VasoType, name: &str) -> VasoType {
+    match op {
+        Token::Plus | Token::PlusAssign => a.checked_add(b).map(wrap).unwrap_or(VasoType::VBit(3, format!("{} Overflow", name))),
+        Token::Minus | Token::MinusAssign => a.checked_sub(b).map(wrap).unwrap_or(VasoType::VBit(3, format!("Negative {}", name))),
+        // Solo razones exactas: 10MB / 3MB no se trunca a 3
+        Token::DivAssign => match a.checked_div(b) {
+            None => VasoType::VBit(3, "Division by Zero".to_string()),
+            Some(_) if !a.is_multiple_of(b) => VasoType::VBit(3, format!("Inexact {} Ratio", name)),
+            Some(r) => i32::try_from(r).map(VasoType::Int).unwrap_or(VasoType::VBit(3, format!("{} Overflow", name))),
+        },
+        Token::LessThan => bool_vbit(a < b),
+        Token::GreaterThan => bool_vbit(a > b),
+        Token::Equals => bool_vbit(a == b),
+        _ => VasoType::VBit(3, format!("Invalid {} Op", name))
     }
 }
 
+// Float con Float o Int: 0.5 + 1, 10 / 4.0
+fn float_op(a: f64, b: f64, op: &Token) -> VasoType {
+    match op {
+        Token::Plus | Token::PlusAssign => VasoType::Float(a + b),
+        Token::Minus | Token::MinusAssign => VasoType::Float(a - b),
+        Token::MulAssign => VasoType::Float(a * b),
+        Token::DivAssign => if b != 0.0 { VasoType::Float(a / b) } else { VasoType::VBit(3, "Division by Zero".to_string()) },
+        Token::LessThan => bool_vbit(a < b),
+        Token::GreaterThan => bool_vbit(a > b),
+        Token::Equals => bool_vbit(a == b),
+        _ => VasoType::VBit(3, "Invalid Float Op".to_string())
+    }
+}
+
+// Duration * Int, Bytes / Int
+fn scale_op(a: u64, n: i32, op: &Token, wrap: fn(u64) -> VasoType, name: &str) -> VasoType {
+    if n < 0 { return VasoType::VBit(3, format!("Negative {}", name)); }
+    match op {
+        Token::MulAssign => a.checked_mul(n as u64).map(wrap).unwrap_or(VasoType::VBit(3, format!("{} Overflow", name))),
+        Token::DivAssign => a.checked_div(n as u64).map(wrap).unwrap_or(VasoType::VBit(3, "Division by Zero".to_string())),
+        _ => VasoType::VBit(3, format!("Invalid {} Op", name))
+    }
+}
+
+// Condiciones de if/while: solo cuenta un `on` producido por la comparacion,
+// no uno heredado por infeccion de un operando VBit.
+pub fn compare(l: &VasoType, r: &VasoType, op: &Token) -> bool {
+    // Un registro de Sys.exec se compara por su status: res == error
+    let (l, r) = (l.state(), r.state());
+    if matches!(op, Token::NotEquals) { return !compare(l, r, &Token::Equals); }
+    if matches!(op, Token::Equals) && l == r { return true; }
+    if matches!(l, VasoType::VBit(..)) != matches!(r, VasoType::VBit(..)) { return false; }
+    matches!(apply_op(l.clone(), r, op), VasoType::VBit(1, _))
+}
+
 pub fn apply_op(l: VasoType, r: &VasoType, op: &Token) -> VasoType {
+    let l = if let VasoType::Map(_) = l { l.state().clone() } else { l };
+    let r = r.state();
     match (l, r) {
         // --- A. ARITMÉTICA PURA (Int vs Int) ---
         (VasoType::Int(a), VasoType::Int(b)) => {
@@ -42,6 +130,10 @@ pub fn apply_op(l: VasoType, r: &VasoType, op: &Token) -> VasoType {
             }
         },
 
+        (VasoType::Float(a), VasoType::Float(b)) => float_op(a, *b, op),
+        (VasoType::Float(a), VasoType::Int(b)) => float_op(a, *b as f64, op),
+        (VasoType::Int(a), VasoType::Float(b)) => float_op(a as f64, *b, op),
+
         // --- B. MANIPULACIÓN DE TEXTO (Str vs Str) ---
         (VasoType::Str(a), VasoType::Str(b)) => {
             match op {
@@ -54,10 +146,7 @@ pub fn apply_op(l: VasoType, r: &VasoType, op: &Token) -> VasoType {
         // --- C. MAGIA DE V-BITS (Dominancia de Estado) ---
         (VasoType::VBit(l_lvl, l_msg), VasoType::VBit(r_lvl, r_msg)) => {
             if matches!(op, Token::Plus | Token::PlusAssign) {
-                let l_p = get_vbit_priority(l_lvl);
-                let r_p = get_vbit_priority(*r_lvl);
-                
-                if l_p >= r_p {
+                if dominates(l_lvl, *r_lvl) {
                      VasoType::VBit(l_lvl, l_msg)
                 } else {
                      VasoType::VBit(*r_lvl, r_msg.clone())
@@ -69,10 +158,83 @@ pub fn apply_op(l: VasoType, r: &VasoType, op: &Token) -> VasoType {
             }
         },
 
-        // Caso D: INFECCIÓN
+        // --- D. UNIDADES (Duration / Bytes) ---
+        (VasoType::Duration(a), VasoType::Duration(b)) => unit_op(a, *b, op, VasoType::Duration, "Duration"),
+        (VasoType::Bytes(a), VasoType::Bytes(b)) => unit_op(a, *b, op, VasoType::Bytes, "Bytes"),
+        (VasoType::Duration(a), VasoType::Int(n)) => scale_op(a, *n, op, VasoType::Duration, "Duration"),
+        (VasoType::Bytes(a), VasoType::Int(n)) => scale_op(a, *n, op, VasoType::Bytes, "Bytes"),
+
+        // --- E. FECHAS (DateTime ± Duration, DateTime - DateTime) ---
+        (VasoType::DateTime(t), VasoType::Duration(ms)) => {
+            let delta = chrono::Duration::milliseconds(*ms as i64);
+            let shifted = match op {
+                Token::Plus | Token::PlusAssign => t.checked_add_signed(delta),
+                Token::Minus | Token::MinusAssign => t.checked_sub_signed(delta),
+                _ => return VasoType::VBit(3, "Invalid DateTime Op".to_string())
+            };
+            shifted.map(VasoType::DateTime).unwrap_or(VasoType::VBit(3, "DateTime Overflow".to_string()))
+        },
+        (VasoType::DateTime(a), VasoType::DateTime(b)) => {
+            match op {
+                Token::Minus | Token::MinusAssign => {
+                    let ms = (a - *b).num_milliseconds();
+                    if ms >= 0 { VasoType::Duration(ms as u64) } else { VasoType::VBit(3, "Negative Duration".to_string()) }
+                },
+                Token::LessThan => bool_vbit(a < *b),
+                Token::GreaterThan => bool_vbit(a > *b),
+                Token::Equals => bool_vbit(a == *b),
+                _ => VasoType::VBit(3, "Invalid DateTime Op".to_string())
+            }
+        },
+
+        // Caso F: INFECCIÓN
         (VasoType::VBit(lvl, msg), _) => VasoType::VBit(lvl, msg),
         (_, VasoType::VBit(lvl, msg)) => VasoType::VBit(*lvl, msg.clone()),
         
         _ => VasoType::VBit(3, "TypeError: Mismatch".to_string())
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit_arithmetic_keeps_the_unit() {
+        assert_eq!(apply_op(VasoType::Duration(1_500), &VasoType::Duration(500), &Token::Plus), VasoType::Duration(2_000));
+        assert_eq!(apply_op(VasoType::Bytes(10), &VasoType::Int(3), &Token::MulAssign), VasoType::Bytes(30));
+        assert_eq!(apply_op(VasoType::Duration(1), &VasoType::Duration(2), &Token::Minus), VasoType::VBit(3, "Negative Duration".to_string()));
+    }
+
+    #[test]
+    fn same_unit_division_is_an_exact_ratio() {
+        assert_eq!(apply_op(VasoType::Bytes(10_000_000), &VasoType::Bytes(500_000), &Token::DivAssign), VasoType::Int(20));
+        assert_eq!(apply_op(VasoType::Bytes(10_000_000), &VasoType::Bytes(3_000_000), &Token::DivAssign), VasoType::VBit(3, "Inexact Bytes Ratio".to_string()));
+        assert_eq!(apply_op(VasoType::Duration(5), &VasoType::Duration(0), &Token::DivAssign), VasoType::VBit(3, "Division by Zero".to_string()));
+    }
+
+    #[test]
+    fn records_compare_by_status() {
+        let failed = VasoType::Map([
+            ("code".to_string(), VasoType::Int(1)),
+            ("status".to_string(), VasoType::VBit(3, "CMD Failed (exit 1)".to_string())),
+        ].into_iter().collect());
+        let error = VasoType::VBit(3, "Generic Error".to_string());
+        let on = VasoType::VBit(1, "".to_string());
+        assert!(compare(&failed, &error, &Token::Equals));
+        assert!(!compare(&failed, &error, &Token::NotEquals));
+        assert!(compare(&failed, &on, &Token::NotEquals));
+        assert!(!compare(&VasoType::Int(1), &on, &Token::Equals));
+    }
+
+    #[test]
+    fn policy_reorders_and_absorbs() {
+        let v = |lvl: u8| VasoType::VBit(lvl, "".to_string());
+        assert_eq!(apply_op(v(4), &v(1), &Token::Plus), v(4));
+        let previous = set_policy(Policy { order: [3, 2, 1, 4, 0], off_absorbing: false });
+        assert_eq!(apply_op(v(4), &v(1), &Token::Plus), v(1));
+        set_policy(Policy { order: [3, 2, 1, 4, 0], off_absorbing: true });
+        assert_eq!(apply_op(v(1), &v(0), &Token::Plus), v(0));
+        set_policy(previous);
+        assert_eq!(apply_op(v(1), &v(0), &Token::Plus), v(1));
+    }
+}
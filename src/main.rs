@@ -4,17 +4,37 @@ mod logic;
 mod stdlib;
 mod parser;
 mod memory;
+mod resilience;
+mod process;
+mod datetime;
+mod environ;
+mod files;
+mod paths;
+mod locks;
+mod params;
+mod json;
+mod schema;
+mod formats;
+mod tables;
+mod strings;
+mod patterns;
+mod lists;
+mod vbits;
+mod numbers;
 
 use logos::Logos;
 use colored::*;
 use std::env;
 use std::fs;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use types::{VasoType, StructDef};
 use tokens::Token;
-use logic::apply_op; 
-use stdlib::call_std_function;
-use parser::extract_args;
+use logic::apply_op;
+use stdlib::{call_std_function, sleep_ms, set_script_args};
+use parser::{eval_condition, eval_expr, extract_args, parse_lock_header, parse_policy_header, parse_retry_header, parse_timeout_header, vbit_literal, token_value};
+use process::{cleanup_background, install_signal_cleanup};
+use resilience::{RetryPolicy, describe_failure, exhausted, push_deadline, pop_deadline, timeout_error, format_millis};
+use std::time::{Duration, Instant};
 use memory::MemoryStack;
 use std::ops::Range;
 
@@ -29,17 +49,56 @@ fn report_error(msg: &str, span: &Range<usize>, code: &str) {
     println!("   >> {}", msg.white());
 }
 
+fn report_warning(msg: &str, span: &Range<usize>, code: &str) {
+    let line = get_line_number(code, span.start);
+    println!("{} {} {}", "⚠️  WARNING [Line".yellow().bold(), line.to_string().yellow().bold(), "]:".yellow().bold());
+    println!("   >> {}", msg.white());
+}
+
 fn main() {
+    datetime::start_clock();
     println!("{}", "\n🥃  VASO ENGINE v3.0 (Performance & Resilience)".bold().cyan());
     println!("{}", "===============================================".cyan());
 
     let args: Vec<String> = env::args().collect();
-    if args.len() < 2 { println!("❌ Uso: cargo run <archivo.vs> [args...]"); return; }
+    let usage = "❌ Uso: vaso run [--timeout 10m] [--seed 42] <archivo.vs> [--param valor...] [args...]";
+
+    // Flags del motor (antes del script). Lo que va despues del .vs es para los params y Sys.arg
+    let mut script_timeout: Option<u64> = None;
+    let mut arg_pos = if args.get(1).is_some_and(|a| a == "run") { 2 } else { 1 };
+    while let Some(flag) = args.get(arg_pos).filter(|a| a.starts_with("--")) {
+        match flag.as_str() {
+            "--timeout" => {
+                // Mismo lexer que el codigo: acepta 10m, 30s o milisegundos
+                let value = args.get(arg_pos + 1).map(|v| Token::lexer(v).collect::<Vec<_>>());
+                match value.as_deref() {
+                    Some([Ok(Token::DurationLiteral(ms))]) => script_timeout = Some(*ms),
+                    Some([Ok(Token::NumberLiteral(ms))]) if *ms >= 0 => script_timeout = Some(*ms as u64),
+                    _ => { println!("❌ --timeout expects a duration (10m, 30s) or milliseconds"); return; }
+                }
+                arg_pos += 2;
+            },
+            "--seed" => {
+                // Math.random / Math.choice / jitter reproducibles: vaso run --seed 42 chaos.vs
+                match args.get(arg_pos + 1).and_then(|v| v.parse::<u64>().ok()) {
+                    Some(n) => numbers::seed(n),
+                    None => { println!("❌ --seed expects a non-negative integer"); return; }
+                }
+                arg_pos += 2;
+            },
+            _ => { println!("❌ Unknown flag: {}", flag); println!("{}", usage); return; }
+        }
+    }
+    if arg_pos >= args.len() { println!("{}", usage); return; }
+    let script_path = &args[arg_pos];
+    let script_args = &args[arg_pos + 1..];
+    paths::set_script_path(script_path);
+    install_signal_cleanup();
     
-    let code = fs::read_to_string(&args[1]).expect("❌ ERROR: No encuentro el archivo .vs");
+    let code = fs::read_to_string(script_path).expect("❌ ERROR: No encuentro el archivo .vs");
     
     // --- 1. LEXER ESTRICTO (Feedback TF: No ignorar errores) ---
-    let tokens: Vec<(Token, Range<usize>)> = Token::lexer(&code)
+    let mut tokens: Vec<(Token, Range<usize>)> = Token::lexer(&code)
         .spanned()
         .map(|(res, span)| {
             match res {
@@ -53,8 +112,30 @@ fn main() {
             }
         })
         .collect();
+    // retry, timeout, param... fuera de su sitio son nombres normales
+    tokens::demote_soft_keywords(&mut tokens);
     
+    // --- PARAMS: `param env: str = "staging";` contra --env prod ---
+    let decls = match params::declarations(&tokens, &code) {
+        Ok(decls) => decls,
+        Err(msg) => { eprintln!("{} {}", "❌ PARAM ERROR:".red().bold(), msg); std::process::exit(2); }
+    };
+    if params::wants_help(&decls, script_args) {
+        params::print_help(script_path, &decls);
+        return;
+    }
+    let bound = match params::bind(&decls, script_args) {
+        Ok(bound) => bound,
+        Err(msg) => {
+            eprintln!("{} {}", "❌ PARAM ERROR:".red().bold(), msg);
+            eprintln!("   Run with --help to see the script's params.");
+            std::process::exit(2);
+        }
+    };
+    set_script_args(bound.positional);
+
     let mut mem_stack = MemoryStack::new();
+    for (name, val) in bound.values { mem_stack.set(name, val); }
     let _structs: HashMap<String, StructDef> = HashMap::new();
     
     // --- 2. JUMP MAP (Feedback TF: Solución O(1) vs O(n²)) ---
@@ -83,6 +164,30 @@ fn main() {
     struct LoopState { depth: usize, start_idx: usize, iter_var: Option<String>, iter_list: Option<Vec<VasoType>>, iter_pos: usize }
     let mut active_loops: Vec<LoopState> = Vec::new();
 
+    // Bloques retry activos. El resultado de cada intento es el ultimo valor evaluado dentro del bloque.
+    // outer_attempt: el `attempt` de fuera (retry anidado), se restaura al salir del bloque
+    struct RetryState { depth: usize, body_idx: usize, resume_idx: usize, attempt: u32, policy: RetryPolicy, failures: Vec<String>, target: Option<String>, outer_attempt: Option<VasoType> }
+    let mut active_retries: Vec<RetryState> = Vec::new();
+    let mut last_value = VasoType::VBit(4, "".to_string());
+    let mut pending_target: Option<String> = None;
+
+    // Bloques timeout activos (cada uno tiene su deadline en la pila de resilience)
+    struct TimeoutState { depth: usize, resume_idx: usize, ms: u64, deadline: Instant, target: Option<String> }
+    let mut active_timeouts: Vec<TimeoutState> = Vec::new();
+
+    // Bloques with_lock activos: el lock se suelta al cerrar el bloque (o al cancelarlo un timeout)
+    struct LockState { depth: usize, path: String, target: Option<String> }
+    let mut active_locks: Vec<LockState> = Vec::new();
+
+    // Bloques policy activos: al cerrar el bloque vuelve la politica anterior
+    struct PolicyState { depth: usize, previous: logic::Policy }
+    let mut active_policies: Vec<PolicyState> = Vec::new();
+    // Un warning por match y estado sin brazo, aunque el match este dentro de un bucle
+    let mut warned_matches: HashSet<(usize, u8)> = HashSet::new();
+
+    let script_deadline = script_timeout.map(|ms| Instant::now() + Duration::from_millis(ms));
+    if let Some(deadline) = script_deadline { push_deadline(deadline); }
+
     // 3. PRE-SCAN DEFINITIONS
     let mut temp_i = 0;
     while temp_i < tokens.len() {
@@ -113,6 +218,47 @@ fn main() {
     let mut i = 0;
     loop {
         if i >= tokens.len() { break; }
+
+        // --- CANCELACION POR TIMEOUT ---
+        if let (Some(deadline), Some(ms)) = (script_deadline, script_timeout)
+            && Instant::now() >= deadline {
+            eprintln!("{} {}", "❌ SCRIPT TIMEOUT:".red().bold(), format!("Execution exceeded {}", format_millis(ms)).white());
+            cleanup_background();
+            locks::release_all();
+            std::process::exit(124);
+        }
+        // El timeout vencido mas externo cancela todo lo que tenga dentro
+        if let Some(pos) = active_timeouts.iter().position(|t| Instant::now() >= t.deadline) {
+            for _ in pos..active_timeouts.len() { pop_deadline(); }
+            let expired = active_timeouts.drain(pos..).next().unwrap();
+            // Los retry cancelados devuelven el `attempt` que habia antes del mas externo
+            let first_cancelled = active_retries.iter().position(|r| r.depth > expired.depth);
+            if let Some(pos) = first_cancelled
+                && let Some(outer) = active_retries.drain(pos..).next().and_then(|r| r.outer_attempt) {
+                mem_stack.set("attempt".to_string(), outer);
+            }
+            active_loops.retain(|l| l.depth <= expired.depth);
+            while active_locks.last().is_some_and(|l| l.depth > expired.depth) {
+                locks::release(&active_locks.pop().unwrap().path);
+            }
+            while active_policies.last().is_some_and(|p| p.depth > expired.depth) {
+                logic::set_policy(active_policies.pop().unwrap().previous);
+            }
+            while let Some(&(_, base_depth)) = call_stack.last() {
+                if base_depth < expired.depth { break; }
+                call_stack.pop();
+                mem_stack.pop_scope();
+            }
+            let result = timeout_error(expired.ms);
+            if let Some(target) = expired.target {
+                mem_stack.set(target, result.clone());
+            }
+            last_value = result;
+            current_depth = expired.depth - 1;
+            i = expired.resume_idx;
+            continue;
+        }
+
         let (current_token, _current_span) = &tokens[i];
         let mut consumed = false; 
 
@@ -124,7 +270,7 @@ fn main() {
                 while scan < tokens.len() {
                     if let (Token::LBrace, _) = tokens[scan] {
                         if let Some(&end_idx) = jump_map.get(&scan) {
-                            i = end_idx; // TELETRANSPORTACION ⚡
+                            i = end_idx + 1; // TELETRANSPORTACION ⚡ (incluye el '}')
                             consumed = true;
                         }
                         break;
@@ -138,28 +284,99 @@ fn main() {
             
             Token::RBrace => { 
                 let mut handled = false;
-                
+
+                // Policy Logic: el bloque termino, vuelve la politica de fuera
+                if let Some(scope) = active_policies.last()
+                    && scope.depth == current_depth {
+                    logic::set_policy(active_policies.pop().unwrap().previous);
+                    i += 1;
+                    current_depth -= 1;
+                    consumed = true;
+                    handled = true;
+                }
+
+                // Lock Logic: el bloque termino, soltamos el lock
+                if !handled
+                    && let Some(lock) = active_locks.last()
+                    && lock.depth == current_depth {
+                    let lock = active_locks.pop().unwrap();
+                    locks::release(&lock.path);
+                    if let Some(target) = lock.target {
+                        mem_stack.set(target, last_value.clone());
+                    }
+                    i += 1;
+                    current_depth -= 1;
+                    consumed = true;
+                    handled = true;
+                }
+
+                // Timeout Logic: el bloque termino a tiempo, su valor es el ultimo evaluado
+                if !handled
+                    && let Some(timeout) = active_timeouts.last()
+                    && timeout.depth == current_depth {
+                    let timeout = active_timeouts.pop().unwrap();
+                    pop_deadline();
+                    if let Some(target) = timeout.target {
+                        mem_stack.set(target, last_value.clone());
+                    }
+                    i = timeout.resume_idx;
+                    current_depth -= 1;
+                    consumed = true;
+                    handled = true;
+                }
+
+                // Retry Logic: el ultimo valor del bloque decide si se repite
+                if !handled
+                    && let Some(retry) = active_retries.last_mut()
+                    && retry.depth == current_depth {
+                    let result = last_value.clone();
+                    if retry.policy.should_retry(&result) {
+                        retry.failures.push(describe_failure(&result));
+                    }
+                    if retry.policy.should_retry(&result) && retry.attempt < retry.policy.max_attempts {
+                        sleep_ms(retry.policy.delay_ms(retry.attempt));
+                        retry.attempt += 1;
+                        mem_stack.set("attempt".to_string(), VasoType::Int(retry.attempt as i32));
+                        last_value = VasoType::VBit(4, "".to_string());
+                        i = retry.body_idx;
+                    } else {
+                        let retry = active_retries.pop().unwrap();
+                        if let Some(outer) = retry.outer_attempt {
+                            mem_stack.set("attempt".to_string(), outer);
+                        }
+                        let final_val = if retry.policy.should_retry(&result) { exhausted(&retry.failures) } else { result };
+                        if let Some(target) = retry.target {
+                            mem_stack.set(target, final_val.clone());
+                        }
+                        last_value = final_val;
+                        i = retry.resume_idx;
+                    }
+                    current_depth -= 1;
+                    consumed = true;
+                    handled = true;
+                }
+
                 // Loop Logic
-                if let Some(loop_state) = active_loops.last_mut() {
-                    if loop_state.depth == current_depth {
-                        if let (Some(var_name), Some(list)) = (&loop_state.iter_var, &loop_state.iter_list) {
-                            loop_state.iter_pos += 1;
-                            if loop_state.iter_pos < list.len() {
-                                mem_stack.set(var_name.clone(), list[loop_state.iter_pos].clone());
-                                i = loop_state.start_idx;
-                                current_depth -= 1;
-                                consumed = true;
-                                handled = true;
-                            } else {
-                                active_loops.pop(); 
-                            }
-                        } else {
-                            // While
-                            i = loop_state.start_idx; 
-                            current_depth -= 1; 
-                            consumed = true; 
+                if !handled
+                    && let Some(loop_state) = active_loops.last_mut()
+                    && loop_state.depth == current_depth {
+                    if let (Some(var_name), Some(list)) = (&loop_state.iter_var, &loop_state.iter_list) {
+                        loop_state.iter_pos += 1;
+                        if loop_state.iter_pos < list.len() {
+                            mem_stack.set(var_name.clone(), list[loop_state.iter_pos].clone());
+                            i = loop_state.start_idx;
+                            current_depth -= 1;
+                            consumed = true;
                             handled = true;
+                        } else {
+                            active_loops.pop(); 
                         }
+                    } else {
+                        // While
+                        i = loop_state.start_idx; 
+                        current_depth -= 1; 
+                        consumed = true; 
+                        handled = true;
                     }
                 }
                 
@@ -176,30 +393,34 @@ fn main() {
                     }
                 }
                 if !handled {
-                    if current_depth > 0 { current_depth -= 1; } 
+                    current_depth = current_depth.saturating_sub(1);
                 }
             },
 
             Token::For => {
-                if let (Some((Token::Identifier(var_name), _)), Some((Token::In, _)), Some((Token::Identifier(list_name), _))) = 
-                       (tokens.get(i+1), tokens.get(i+2), tokens.get(i+3)) {
-                    
-                    let mut list_val = None;
-                    if let Some(VasoType::List(l)) = mem_stack.get(list_name) {
-                        list_val = Some(l.clone());
-                    }
+                if let (Some((Token::Identifier(var_name), _)), Some((Token::In, _))) = (tokens.get(i+1), tokens.get(i+2)) {
+                    // for pod in pods { } / for c in Json.get(doc, "spec.containers") { }
+                    let (iterable, body_idx) = eval_expr(&tokens, i+3, &mem_stack);
+                    let list_val = match iterable {
+                        VasoType::List(l) => Some(l),
+                        VasoType::Json(serde_json::Value::Array(items)) => Some(items.iter().map(json::to_native).collect()),
+                        other => {
+                            report_error(&format!("FOR loop expects a List, got {}", other.render()), _current_span, &code);
+                            None
+                        }
+                    };
 
                     if let Some(list) = list_val {
                         let loop_depth = current_depth + 1;
                         let mut enter_loop = false;
-                        let is_active = active_loops.last().map(|l| l.depth == loop_depth && l.start_idx == i + 4).unwrap_or(false);
+                        let is_active = active_loops.last().map(|l| l.depth == loop_depth && l.start_idx == body_idx).unwrap_or(false);
 
                         if !is_active {
                             if !list.is_empty() {
                                 mem_stack.set(var_name.clone(), list[0].clone());
                                 active_loops.push(LoopState { 
                                     depth: loop_depth, 
-                                    start_idx: i + 4, 
+                                    start_idx: body_idx, 
                                     iter_var: Some(var_name.clone()),
                                     iter_list: Some(list),
                                     iter_pos: 0
@@ -211,49 +432,24 @@ fn main() {
                         }
 
                         if enter_loop {
-                            i += 4; 
+                            i = body_idx; 
                             consumed = true; 
                         } else {
                             // SKIP OPTIMIZADO
-                            if let Some((Token::LBrace, _)) = tokens.get(i+4) {
-                                if let Some(&end_idx) = jump_map.get(&(i+4)) {
-                                    i = end_idx; // TELETRANSPORTACION ⚡
-                                    consumed = true;
-                                }
+                            if let Some((Token::LBrace, _)) = tokens.get(body_idx)
+                                && let Some(&end_idx) = jump_map.get(&body_idx) {
+                                i = end_idx + 1; // TELETRANSPORTACION ⚡ (incluye el '}')
+                                consumed = true;
                             }
                         }
-                    } else {
-                        report_error("FOR loop expects a List variable", _current_span, &code);
                     }
                 }
             },
 
             Token::While => {
-                let mut cond = false;
-                let mut offset = 1;
-                // ... (Lógica de condiciones igual que antes) ...
-                if let Some((Token::Identifier(n1), _)) = tokens.get(i+1) {
-                    if let (Some((Token::LessThan, _)), Some((Token::NumberLiteral(v2), _))) = (tokens.get(i+2), tokens.get(i+3)) {
-                         if let Some(VasoType::Int(v1)) = mem_stack.get(n1) { if v1 < v2 { cond = true; } }
-                         offset = 3;
-                    }
-                    else if let (Some((Token::LessThan, _)), Some((Token::Identifier(n2), _))) = (tokens.get(i+2), tokens.get(i+3)) {
-                         if let (Some(VasoType::Int(v1)), Some(VasoType::Int(v2))) = (mem_stack.get(n1), mem_stack.get(n2)) { 
-                             if v1 < v2 { cond = true; } 
-                         }
-                         offset = 3;
-                    }
-                    else if let (Some((Token::Equals, _)), Some((Token::NumberLiteral(v2), _))) = (tokens.get(i+2), tokens.get(i+3)) {
-                         if let Some(VasoType::Int(v1)) = mem_stack.get(n1) { if v1 == v2 { cond = true; } }
-                         offset = 3;
-                    }
-                    else if let (Some((Token::Equals, _)), Some((Token::Identifier(n2), _))) = (tokens.get(i+2), tokens.get(i+3)) {
-                         if let (Some(VasoType::Int(v1)), Some(VasoType::Int(v2))) = (mem_stack.get(n1), mem_stack.get(n2)) { 
-                             if v1 == v2 { cond = true; } 
-                         }
-                         offset = 3;
-                    }
-                }
+                // cond: <expr> (<|>|==|!=) <expr>  |  <expr vbit>
+                let (cond, brace) = eval_condition(&tokens, i+1, &mem_stack);
+                let offset = brace - i - 1;
 
                 let loop_depth = current_depth + 1;
                 if cond {
@@ -263,16 +459,15 @@ fn main() {
                     }
                     i += offset;
                 } else {
-                    if let Some(last) = active_loops.last() {
-                        if last.depth == loop_depth { active_loops.pop(); }
-                    }
+                    if let Some(last) = active_loops.last()
+                        && last.depth == loop_depth { active_loops.pop(); }
                     // SKIP OPTIMIZADO
                     // Buscamos la llave de apertura
                     let mut brace_idx = i;
                     while brace_idx < tokens.len() {
                         if let (Token::LBrace, _) = tokens[brace_idx] {
                             if let Some(&end_idx) = jump_map.get(&brace_idx) {
-                                i = end_idx; // TELETRANSPORTACION ⚡
+                                i = end_idx + 1; // TELETRANSPORTACION ⚡ (incluye el '}')
                                 consumed = true;
                             }
                             break;
@@ -286,64 +481,24 @@ fn main() {
                 // ... (Lógica de print igual) ...
                 if let Some((Token::LParen, _)) = tokens.get(i+1) {
                     if let (Some((Token::Identifier(n), _)), Some((Token::RParen, _))) = (tokens.get(i+2), tokens.get(i+3)) {
-                        if let Some(v) = mem_stack.get(n) { println!("{}", v); } else { println!(""); } 
+                        if let Some(v) = mem_stack.get(n) { println!("{}", v); } else { println!(); } 
                         i += 4; consumed = true;
                     }
-                    else if let (Some((Token::StringLiteral(s), _)), Some((Token::RParen, _))) = (tokens.get(i+2), tokens.get(i+3)) {
-                         println!("{}", s);
-                         i += 4; consumed = true;
-                    }
-                    else if let (Some(token_lit), Some((Token::RParen, _))) = (tokens.get(i+2), tokens.get(i+3)) {
-                         match token_lit {
-                             (Token::LitOff, _) => println!("off"),
-                             (Token::LitOn, _) => println!("on"),
-                             (Token::LitLoading, _) => println!("loading"),
-                             (Token::LitError, _) => println!("error"),
-                             (Token::LitUnknown, _) => println!("unknown"),
-                             _ => {}
-                         }
-                         i += 4; consumed = true;
+                    else {
+                        // Literales, "${interpolacion}", llamadas y expresiones: print("x = ${x + 1}")
+                        let (val, next) = eval_expr(&tokens, i+2, &mem_stack);
+                        if let Some((Token::RParen, _)) = tokens.get(next) {
+                            println!("{}", val);
+                            i = next + 1; consumed = true;
+                        }
                     }
                 }
             },
 
             Token::If => {
-                let mut cond = false;
-                let mut offset = 1;
-                // ... (Lógica de condiciones If igual) ...
-                if let Some((Token::Identifier(n1), _)) = tokens.get(i+1) {
-                    if let (Some((Token::GreaterThan, _)), Some((Token::Identifier(n2), _))) = (tokens.get(i+2), tokens.get(i+3)) {
-                         if let (Some(VasoType::Int(v1)), Some(VasoType::Int(v2))) = (mem_stack.get(n1), mem_stack.get(n2)) {
-                             if v1 > v2 { cond = true; }
-                         }
-                         offset = 3;
-                    } 
-                    else if let (Some((Token::GreaterThan, _)), Some((Token::NumberLiteral(v2), _))) = (tokens.get(i+2), tokens.get(i+3)) {
-                         if let Some(VasoType::Int(v1)) = mem_stack.get(n1) {
-                             if v1 > v2 { cond = true; }
-                         }
-                         offset = 3;
-                    } 
-                    else if let (Some((Token::Equals, _)), Some((Token::StringLiteral(s2), _))) = (tokens.get(i+2), tokens.get(i+3)) {
-                         if let Some(VasoType::Str(s1)) = mem_stack.get(n1) {
-                             if s1 == s2 { cond = true; }
-                         }
-                         offset = 3;
-                    } 
-                    else if let (Some((Token::Equals, _)), Some((Token::NumberLiteral(v2), _))) = (tokens.get(i+2), tokens.get(i+3)) {
-                         if let Some(VasoType::Int(v1)) = mem_stack.get(n1) {
-                             if v1 == v2 { cond = true; }
-                         }
-                         offset = 3;
-                    }
-                    else if let (Some((Token::Equals, _)), Some((Token::Identifier(n2), _))) = (tokens.get(i+2), tokens.get(i+3)) {
-                         if let (Some(val1), Some(val2)) = (mem_stack.get(n1), mem_stack.get(n2)) {
-                             if val1 == val2 { cond = true; }
-                         }
-                         offset = 3;
-                    }
-                    else if let Some(VasoType::VBit(1, _)) = mem_stack.get(n1) { cond = true; }
-                }
+                // cond: <expr> (<|>|==|!=) <expr>  |  <expr vbit>: if Regex.match(r"ERROR", line) {
+                let (cond, brace) = eval_condition(&tokens, i+1, &mem_stack);
+                let offset = brace - i - 1;
 
                 if cond { 
                     skip_else_at_depth.insert(current_depth, true);
@@ -355,7 +510,7 @@ fn main() {
                     while brace_idx < tokens.len() {
                         if let (Token::LBrace, _) = tokens[brace_idx] {
                             if let Some(&end_idx) = jump_map.get(&brace_idx) {
-                                i = end_idx; // TELETRANSPORTACION ⚡
+                                i = end_idx + 1; // TELETRANSPORTACION ⚡ (incluye el '}')
                                 consumed = true;
                             }
                             break;
@@ -369,18 +524,17 @@ fn main() {
                 let should_skip = *skip_else_at_depth.get(&current_depth).unwrap_or(&false);
                 if should_skip {
                     // SKIP OPTIMIZADO
-                    if let (Token::LBrace, _) = tokens[i+1] {
-                        if let Some(&end_idx) = jump_map.get(&(i+1)) {
-                            i = end_idx; // TELETRANSPORTACION ⚡
-                            consumed = true;
-                        }
+                    if let (Token::LBrace, _) = tokens[i+1]
+                        && let Some(&end_idx) = jump_map.get(&(i+1)) {
+                        i = end_idx + 1; // TELETRANSPORTACION ⚡ (incluye el '}')
+                        consumed = true;
                     }
                 }
             },
 
             Token::Match => {
                if let (Some((Token::Identifier(var_name), _)), Some((Token::LBrace, _))) = (tokens.get(i+1), tokens.get(i+2)) {
-                    let current_val = mem_stack.get(var_name).cloned().unwrap_or(VasoType::VBit(4, "".to_string())); 
+                    let current_val = mem_stack.get(var_name).map(|v| v.state().clone()).unwrap_or(VasoType::VBit(4, "".to_string()));
                     let mut j = i + 3;
                     let mut match_executed = false;
                     
@@ -388,14 +542,13 @@ fn main() {
                         if let (Token::RBrace, _) = tokens[j] { break; } 
                         
                         if let (Some((case_token, _)), Some((Token::Arrow, _)), Some((Token::LBrace, _))) = (tokens.get(j), tokens.get(j+1), tokens.get(j+2)) {
-                            let matches_case = match (case_token, &current_val) {
-                                (Token::LitOn, VasoType::VBit(1, _)) => true,
-                                (Token::LitOff, VasoType::VBit(0, _)) => true,
-                                (Token::LitLoading, VasoType::VBit(2, _)) => true,
-                                (Token::LitError, VasoType::VBit(3, _)) => true,
-                                (Token::LitUnknown, VasoType::VBit(4, _)) => true,
-                                _ => false
-                            };
+                            let matches_case = matches!((case_token, &current_val),
+                                (Token::LitOn, VasoType::VBit(1, _)) |
+                                (Token::LitOff, VasoType::VBit(0, _)) |
+                                (Token::LitLoading, VasoType::VBit(2, _)) |
+                                (Token::LitError, VasoType::VBit(3, _)) |
+                                (Token::LitUnknown, VasoType::VBit(4, _))
+                            );
 
                             if matches_case && !match_executed {
                                 match_executed = true;
@@ -407,35 +560,27 @@ fn main() {
                                         (Token::Print, _) => {
                                              if let (Some((Token::LParen, _)), Some((Token::StringLiteral(s), _)), Some((Token::RParen, _))) = (tokens.get(k+1), tokens.get(k+2), tokens.get(k+3)) {
                                                  println!("{}", s);
-                                             } else if let (Some((Token::LParen, _)), Some((Token::Identifier(n), _)), Some((Token::RParen, _))) = (tokens.get(k+1), tokens.get(k+2), tokens.get(k+3)) {
-                                                 if let Some(v) = mem_stack.get(n) { println!("{}", v); }
+                                             } else if let (Some((Token::LParen, _)), Some((Token::TemplateLiteral(_), _)), Some((Token::RParen, _))) = (tokens.get(k+1), tokens.get(k+2), tokens.get(k+3))
+                                                 && let Some(v) = token_value(&tokens[k+2].0, &mem_stack) {
+                                                 println!("{}", v);
+                                             } else if let (Some((Token::LParen, _)), Some((Token::Identifier(n), _)), Some((Token::RParen, _))) = (tokens.get(k+1), tokens.get(k+2), tokens.get(k+3))
+                                                 && let Some(v) = mem_stack.get(n) {
+                                                 println!("{}", v);
                                              }
                                              k += 3;
                                         },
                                         (Token::Identifier(target_name), _) => {
                                             if let (Some((assign, _)), Some((val_token, _))) = (tokens.get(k+1), tokens.get(k+2)) {
                                                 if matches!(assign, Token::AssignC | Token::AssignPascal) {
-                                                    let val_to_assign = match val_token {
-                                                        Token::NumberLiteral(n) => Some(VasoType::Int(*n)),
-                                                        Token::StringLiteral(s) => Some(VasoType::Str(s.clone())),
-                                                        Token::Identifier(source_name) => mem_stack.get(source_name).cloned(),
-                                                        _ => None
-                                                    };
-                                                    if let Some(v) = val_to_assign {
+                                                    if let Some(v) = token_value(val_token, &mem_stack) {
                                                         mem_stack.set(target_name.clone(), v);
                                                     }
                                                     k += 2;
-                                                } else if matches!(assign, Token::PlusAssign | Token::MinusAssign) {
-                                                     let r_val_opt = match val_token {
-                                                         Token::NumberLiteral(n) => Some(VasoType::Int(*n)),
-                                                         Token::Identifier(n) => mem_stack.get(n).cloned(),
-                                                         _ => None
-                                                     };
-                                                     if let Some(r_val) = r_val_opt {
-                                                         if let Some(l_val) = mem_stack.get(target_name) {
-                                                             let res = apply_op(l_val.clone(), &r_val, assign);
-                                                             mem_stack.set(target_name.clone(), res);
-                                                         }
+                                                } else if matches!(assign, Token::PlusAssign | Token::MinusAssign | Token::MulAssign | Token::DivAssign) {
+                                                     if let Some(r_val) = token_value(val_token, &mem_stack)
+                                                         && let Some(l_val) = mem_stack.get(target_name) {
+                                                         let res = apply_op(l_val.clone(), &r_val, assign);
+                                                         mem_stack.set(target_name.clone(), res);
                                                      }
                                                      k += 2;
                                                 }
@@ -458,11 +603,19 @@ fn main() {
                         j += 1;
                     }
                     
+                    // Un estado que domina a `on` (segun la politica) y no tiene brazo se perderia en silencio
+                    if !match_executed
+                        && let VasoType::VBit(lvl, _) = current_val
+                        && logic::needs_arm(lvl)
+                        && warned_matches.insert((i, lvl)) {
+                        report_warning(&format!("match on '{}' has no arm for {}; the block was skipped", var_name, types::vbit_name(lvl)), _current_span, &code);
+                    }
+
                     // --- SKIP OPTIMIZADO (MATCH BLOCK ENTERO) ---
                     // Saltamos todo el bloque match
                     // i+2 es la posicion del LBrace '{' principal del match
                     if let Some(&end_idx) = jump_map.get(&(i+2)) {
-                        i = end_idx;
+                        i = end_idx + 1;
                         consumed = true;
                     }
                 }
@@ -486,115 +639,203 @@ fn main() {
                         consumed = true;
                     }
                 }
-                
-                if !consumed {
-                    if let (Some((assign, _span)), Some((val_token, val_span))) = (tokens.get(i+1), tokens.get(i+2)) {
-                        if matches!(assign, Token::AssignC | Token::AssignPascal) {
-                            let final_val;
-                            if let Token::LBracket = val_token {
-                                let mut list_items = Vec::new();
-                                let mut k = i + 3;
-                                while k < tokens.len() {
-                                    match &tokens[k] {
-                                        (Token::RBracket, _) => break,
-                                        (Token::NumberLiteral(n), _) => list_items.push(VasoType::Int(*n)),
-                                        (Token::StringLiteral(s), _) => list_items.push(VasoType::Str(s.clone())),
-                                        _ => {}
-                                    }
-                                    k += 1;
-                                }
-                                final_val = Some(VasoType::List(list_items));
-                                i = k; 
-                            }
-                            else {
-                                final_val = match val_token {
-                                    Token::LitOff => Some(VasoType::VBit(0, "".to_string())),
-                                    Token::LitOn => Some(VasoType::VBit(1, "".to_string())),
-                                    Token::LitLoading => Some(VasoType::VBit(2, "".to_string())),
-                                    Token::LitError => Some(VasoType::VBit(3, "Generic Error".to_string())), 
-                                    Token::LitUnknown => Some(VasoType::VBit(4, "".to_string())), 
-                                    Token::NumberLiteral(n) => Some(VasoType::Int(*n)),
-                                    Token::StringLiteral(s) => Some(VasoType::Str(s.clone())),
-                                    Token::Identifier(n) => mem_stack.get(n).cloned(),
-                                    _ => None
-                                };
-                            }
 
-                            if let Some(v) = final_val { 
-                                mem_stack.set(name.clone(), v); 
-                                if !matches!(val_token, Token::LBracket) { i += 3; }
-                                consumed = true; 
-                            } else {
-                                if !matches!(val_token, Token::Identifier(_)) {
-                                    report_error("Invalid assignment value", val_span, &code);
-                                }
-                            }
-                        }
-                        else if matches!(assign, Token::PlusAssign | Token::MinusAssign) { 
-                             let r_val_opt = match val_token {
-                                 Token::LitOff => Some(VasoType::VBit(0, "".to_string())),
-                                 Token::LitOn => Some(VasoType::VBit(1, "".to_string())),
-                                 Token::LitLoading => Some(VasoType::VBit(2, "".to_string())),
-                                 Token::LitError => Some(VasoType::VBit(3, "Generic Error".to_string())),
-                                 Token::LitUnknown => Some(VasoType::VBit(4, "".to_string())),
-                                 Token::NumberLiteral(n) => Some(VasoType::Int(*n)),
-                                 Token::StringLiteral(s) => Some(VasoType::Str(s.clone())),
-                                 Token::Identifier(n) => mem_stack.get(n).cloned(),
-                                 _ => None
-                             };
-
-                             if let Some(r_val) = r_val_opt {
-                                 if let Some(l_val) = mem_stack.get(name) {
-                                     let res = apply_op(l_val.clone(), &r_val, assign);
-                                     mem_stack.set(name.clone(), res);
-                                     i += 3; consumed = true;
-                                 }
-                             }
+                // Llamada suelta a la stdlib: Time.sleep(500); Sys.exec("npm", "test");
+                if !consumed
+                    && let (Some((Token::Dot, _)), Some((Token::Identifier(func), _)), Some((Token::LParen, _))) = (tokens.get(i+1), tokens.get(i+2), tokens.get(i+3)) {
+                    if let Some(target) = mem_stack.get(name).cloned() {
+                        // Metodo sobre una variable: server.kill();
+                        let (v, next_idx) = eval_expr(&tokens, i, &mem_stack);
+                        // queue.push(job); -> la lista nueva se guarda en la variable (si no hay error)
+                        if let VasoType::List(_) = target
+                            && lists::IN_PLACE.contains(&func.as_str())
+                            && matches!(tokens.get(next_idx), Some((Token::Semicolon, _)))
+                            && let VasoType::List(_) = v {
+                            mem_stack.set(name.clone(), v.clone());
                         }
+                        last_value = v;
+                        i = next_idx;
+                    } else {
+                        let (args, next_idx) = extract_args(&tokens, i+3, &mem_stack);
+                        last_value = call_std_function(name, func, args);
+                        i = next_idx;
+                    }
+                    consumed = true;
+                }
+                
+                if !consumed
+                    && let (Some((assign, _span)), Some(_)) = (tokens.get(i+1), tokens.get(i+2)) {
+                    if matches!(assign, Token::AssignC | Token::AssignPascal) {
+                        let (v, next_idx) = eval_expr(&tokens, i+2, &mem_stack);
+                        mem_stack.set(name.clone(), v.clone());
+                        last_value = v;
+                        i = next_idx; consumed = true;
+                    }
+                    else if matches!(assign, Token::PlusAssign | Token::MinusAssign | Token::MulAssign | Token::DivAssign) { 
+                         let (r_val, next_idx) = eval_expr(&tokens, i+2, &mem_stack);
+                         if let Some(l_val) = mem_stack.get(name) {
+                             let res = apply_op(l_val.clone(), &r_val, assign);
+                             mem_stack.set(name.clone(), res.clone());
+                             last_value = res;
+                             i = next_idx; consumed = true;
+                         }
                     }
                 }
             },
             Token::Value | Token::Variable => {
                 // ... (Lógica de var igual que antes) ...
                 if let (Some((Token::Identifier(name), _)), Some((assign, _)), Some((val_token, _))) = 
-                       (tokens.get(i+1), tokens.get(i+2), tokens.get(i+3)) {
-                    if matches!(assign, Token::AssignC | Token::AssignPascal) {
-                        let mut lib_call = false;
-                        if let Token::Identifier(module) = val_token {
-                            if let (Some((Token::Dot, _)), Some((Token::Identifier(func), _))) = (tokens.get(i+4), tokens.get(i+5)) {
-                                let (args, next_idx) = extract_args(&tokens, i+6, &mem_stack);
-                                let result = call_std_function(module, func, args);
-                                mem_stack.set(name.clone(), result);
-                                i = next_idx; consumed = true; lib_call = true;
+                       (tokens.get(i+1), tokens.get(i+2), tokens.get(i+3))
+                    && matches!(assign, Token::AssignC | Token::AssignPascal) {
+                    if let Token::Retry | Token::Timeout | Token::WithLock = val_token {
+                        // var x := retry/timeout/with_lock ... { } -> el valor final se asigna al cerrar el bloque
+                        pending_target = Some(name.clone());
+                        i += 3; consumed = true;
+                    }
+                    else {
+                        let (v, next_idx) = eval_expr(&tokens, i+3, &mem_stack);
+                        mem_stack.set(name.clone(), v.clone());
+                        last_value = v;
+                        i = next_idx; consumed = true;
+                    }
+                }
+            },
+
+            Token::WithLock => {
+                let target = pending_target.take();
+                match parse_lock_header(&tokens, i, &mem_stack) {
+                    Ok((path, wait, body_idx)) => {
+                        let acquired = locks::acquire(&path, wait);
+                        if let VasoType::VBit(1, _) = acquired {
+                            last_value = VasoType::VBit(4, "".to_string());
+                            active_locks.push(LockState { depth: current_depth + 1, path, target });
+                            i = body_idx;
+                        } else {
+                            // Sin lock no se ejecuta el bloque: su valor es el error
+                            if let Some(target) = target {
+                                mem_stack.set(target, acquired.clone());
                             }
+                            last_value = acquired;
+                            i = jump_map.get(&body_idx).copied().unwrap_or(tokens.len() - 1) + 1;
+                        }
+                        consumed = true;
+                    },
+                    Err(msg) => {
+                        report_error(&msg, _current_span, &code);
+                        if let Some(target) = target {
+                            mem_stack.set(target, VasoType::VBit(3, msg));
+                        }
+                    }
+                }
+            },
+
+            Token::Policy => {
+                // policy ...;  rige desde aqui. policy ... { }  solo dentro del bloque
+                match parse_policy_header(&tokens, i) {
+                    Ok((policy, end_idx)) => {
+                        let previous = logic::set_policy(policy);
+                        if let Some((Token::LBrace, _)) = tokens.get(end_idx) {
+                            active_policies.push(PolicyState { depth: current_depth + 1, previous });
+                            i = end_idx;
+                        } else {
+                            i = end_idx + 1;
+                        }
+                        consumed = true;
+                    },
+                    Err(msg) => report_error(&msg, _current_span, &code)
+                }
+            },
+
+            Token::Timeout => {
+                let target = pending_target.take();
+                match parse_timeout_header(&tokens, i, &mem_stack) {
+                    Ok((ms, body_idx)) => {
+                        let end_idx = jump_map.get(&body_idx).copied().unwrap_or(tokens.len() - 1);
+                        let deadline = Instant::now() + Duration::from_millis(ms);
+                        push_deadline(deadline);
+                        last_value = VasoType::VBit(4, "".to_string());
+                        active_timeouts.push(TimeoutState { depth: current_depth + 1, resume_idx: end_idx + 1, ms, deadline, target });
+                        i = body_idx;
+                        consumed = true;
+                    },
+                    Err(msg) => {
+                        report_error(&msg, _current_span, &code);
+                        if let Some(target) = target {
+                            mem_stack.set(target, VasoType::VBit(3, msg));
+                        }
+                        // Sin deadline valido no ejecutamos el bloque
+                        if let Some((Token::LBrace, _)) = tokens.get(i+2)
+                            && let Some(&end_idx) = jump_map.get(&(i+2)) {
+                            i = end_idx + 1;
+                            consumed = true;
                         }
-                        if !lib_call {
-                            match val_token {
-                                Token::NumberLiteral(n) => { mem_stack.set(name.clone(), VasoType::Int(*n)); i += 4; consumed = true; },
-                                Token::StringLiteral(s) => { mem_stack.set(name.clone(), VasoType::Str(s.clone())); i += 4; consumed = true; },
-                                Token::Identifier(src_name) => {
-                                    if let Some(val) = mem_stack.get(src_name).cloned() {
-                                        mem_stack.set(name.clone(), val);
-                                        i += 4; consumed = true;
+                    }
+                }
+            },
+
+            Token::Retry => {
+                let target = pending_target.take();
+                match parse_retry_header(&tokens, i, &mem_stack) {
+                    Ok((mut policy, body_idx)) => {
+                        let end_idx = jump_map.get(&body_idx).copied().unwrap_or(tokens.len() - 1);
+                        let mut resume_idx = end_idx + 1;
+                        if let Some((Token::Until, _)) = tokens.get(end_idx+1) {
+                            resume_idx = end_idx + 3;
+                            match tokens.get(end_idx+2).and_then(|(t, _)| vbit_literal(t)) {
+                                Some(state) => policy.until = Some(state),
+                                None => {
+                                    // `until ready`: sin estado valido no se ejecuta el bloque
+                                    let msg = "until expects a state: on, off, loading, error or unknown".to_string();
+                                    report_error(&msg, _current_span, &code);
+                                    if let Some(target) = target {
+                                        mem_stack.set(target, VasoType::VBit(3, msg));
                                     }
-                                },
-                                Token::LitOn => { mem_stack.set(name.clone(), VasoType::VBit(1, "".to_string())); i += 4; consumed = true; },
-                                Token::LitOff => { mem_stack.set(name.clone(), VasoType::VBit(0, "".to_string())); i += 4; consumed = true; },
-                                Token::LitLoading => { mem_stack.set(name.clone(), VasoType::VBit(2, "".to_string())); i += 4; consumed = true; },
-                                Token::LitError => { mem_stack.set(name.clone(), VasoType::VBit(3, "Generic Error".to_string())); i += 4; consumed = true; },
-                                Token::LitUnknown => { mem_stack.set(name.clone(), VasoType::VBit(4, "".to_string())); i += 4; consumed = true; },
-                                _ => {}
+                                    i = resume_idx;
+                                    continue;
+                                }
+                            }
+                        }
+                        let outer_attempt = mem_stack.get("attempt").cloned();
+                        mem_stack.set("attempt".to_string(), VasoType::Int(1));
+                        last_value = VasoType::VBit(4, "".to_string());
+                        active_retries.push(RetryState { depth: current_depth + 1, body_idx, resume_idx, attempt: 1, policy, failures: Vec::new(), target, outer_attempt });
+                        i = body_idx;
+                        consumed = true;
+                    },
+                    Err(msg) => {
+                        report_error(&msg, _current_span, &code);
+                        if let Some(target) = target {
+                            mem_stack.set(target, VasoType::VBit(3, msg));
+                        }
+                        // Saltamos el bloque (y su clausula until) sin ejecutarlo
+                        let mut brace_idx = i;
+                        while brace_idx < tokens.len() {
+                            if let (Token::LBrace, _) = tokens[brace_idx] {
+                                if let Some(&end_idx) = jump_map.get(&brace_idx) {
+                                    i = end_idx + 1;
+                                    if let Some((Token::Until, _)) = tokens.get(i) { i += 2; }
+                                    consumed = true;
+                                }
+                                break;
                             }
+                            brace_idx += 1;
                         }
                     }
                 }
             },
             
+            Token::Param => {
+                // Ya resuelto antes de ejecutar: saltamos la declaracion
+                while i < tokens.len() && tokens[i].0 != Token::Semicolon { i += 1; }
+                consumed = true;
+            },
             Token::Mold | Token::Semicolon => { i += 1; consumed = true; }, 
             _ => {}
         }
         if !consumed { i += 1; }
     }
+    cleanup_background();
+    locks::release_all();
     println!("---------------");
     println!("{}", "✅ Ejecución finalizada.".green());
 }
\ No newline at end of file
@@ -26,8 +26,8 @@ impl MemoryStack {
         // 1. Buscamos si la variable ya existe en algún scope superior (hacia atrás)
         // Si existe (como el saldo global), la actualizamos ahí mismo.
         for scope in self.scopes.iter_mut().rev() {
-            if scope.contains_key(&name) {
-                scope.insert(name, val);
+            if let Some(slot) = scope.get_mut(&name) {
+                *slot = val;
                 return; // ¡Actualizada! Salimos de la función.
             }
         }
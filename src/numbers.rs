@@ -0,0 +1,214 @@
+use crate::types::VasoType;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cell::RefCell;
+
+// --- MATEMATICAS: Math.random / abs / clamp / pow / sqrt / percentile / stddev ... ---
+// Int y Float se mezclan como en la aritmetica. Lo que no tiene sentido (sqrt(-1), pow que
+// desborda, percentile de una lista vacia) es un `error`, nunca NaN ni un panic.
+// Todo lo aleatorio (Math.random, Math.choice, el jitter de retry) sale del mismo generador:
+// con Math.seed(n) o `vaso run --seed n` una prueba de caos se repite igual.
+
+thread_local! {
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+pub fn seed(n: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(n));
+}
+
+pub fn with_rng<T>(f: impl FnOnce(&mut StdRng) -> T) -> T {
+    RNG.with(|rng| f(&mut rng.borrow_mut()))
+}
+
+fn err(msg: String) -> VasoType {
+    VasoType::VBit(3, msg)
+}
+
+fn as_f64(v: &VasoType) -> Option<f64> {
+    match v {
+        VasoType::Int(n) => Some(*n as f64),
+        VasoType::Float(x) => Some(*x),
+        _ => None,
+    }
+}
+
+// Un resultado Float valido; inf/NaN se reportan como error
+fn float(func: &str, x: f64) -> VasoType {
+    if x.is_finite() { VasoType::Float(x) } else { err(format!("Math.{}: result is not a finite number", func)) }
+}
+
+// floor/ceil/round devuelven Int mientras quepa
+fn to_int(func: &str, x: f64) -> VasoType {
+    if x >= i32::MIN as f64 && x <= i32::MAX as f64 { VasoType::Int(x as i32) }
+    else { err(format!("Math.{}: {} does not fit in an Int", func, x)) }
+}
+
+fn numbers<'a>(func: &str, items: &'a [VasoType]) -> Result<Vec<(f64, &'a VasoType)>, VasoType> {
+    items.iter().map(|v| as_f64(v).map(|x| (x, v)).ok_or_else(|| err(format!("Math.{}: {} is not a number", func, v.render())))).collect()
+}
+
+// Math.min(a, b, ...) o Math.min(lista); el valor gana con su tipo (Int o Float)
+fn extreme(func: &str, args: &[VasoType], want_max: bool) -> VasoType {
+    let items = match args {
+        [VasoType::List(items)] => items.as_slice(),
+        _ => args,
+    };
+    let values = match numbers(func, items) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let chosen = values.iter().fold(None, |acc: Option<&(f64, &VasoType)>, item| match acc {
+        Some(best) if (want_max && item.0 > best.0) || (!want_max && item.0 < best.0) => Some(item),
+        Some(best) => Some(best),
+        None => Some(item),
+    });
+    match chosen {
+        Some((_, v)) => (*v).clone(),
+        None => err(format!("Math.{} needs at least one number", func)),
+    }
+}
+
+// Interpolacion lineal entre los dos vecinos (como numpy): percentile([1, 2, 3, 4], 50) -> 2.5
+fn percentile(items: &[VasoType], p: f64) -> VasoType {
+    if !(0.0..=100.0).contains(&p) { return err(format!("Math.percentile: p must be between 0 and 100, got {}", p)); }
+    let mut values: Vec<f64> = match numbers("percentile", items) {
+        Ok(v) => v.into_iter().map(|(x, _)| x).collect(),
+        Err(e) => return e,
+    };
+    if values.is_empty() { return err("Math.percentile of an empty list".to_string()); }
+    values.sort_by(f64::total_cmp);
+    let rank = p / 100.0 * (values.len() - 1) as f64;
+    let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+    float("percentile", values[low] + (values[high] - values[low]) * (rank - low as f64))
+}
+
+// Desviacion estandar de la poblacion (se divide por n)
+fn stddev(items: &[VasoType]) -> VasoType {
+    let values = match numbers("stddev", items) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    if values.is_empty() { return err("Math.stddev of an empty list".to_string()); }
+    let n = values.len() as f64;
+    let mean = values.iter().map(|(x, _)| x).sum::<f64>() / n;
+    float("stddev", (values.iter().map(|(x, _)| (x - mean).powi(2)).sum::<f64>() / n).sqrt())
+}
+
+fn random(min: &VasoType, max: &VasoType) -> VasoType {
+    match (min, max) {
+        // Enteros: ambos extremos incluidos, como un dado: Math.random(1, 6)
+        (VasoType::Int(a), VasoType::Int(b)) if a <= b => VasoType::Int(with_rng(|rng| rng.gen_range(*a..=*b))),
+        _ => match (as_f64(min), as_f64(max)) {
+            (Some(a), Some(b)) if a < b => VasoType::Float(with_rng(|rng| rng.gen_range(a..b))),
+            (Some(a), Some(b)) if a == b => VasoType::Float(a),
+            (Some(a), Some(b)) => err(format!("Math.random: min {} is greater than max {}", a, b)),
+            _ => err(format!("Math.random needs (min, max) numbers, got ({}, {})", min.render(), max.render())),
+        },
+    }
+}
+
+pub fn call(func: &str, args: &[VasoType]) -> VasoType {
+    use VasoType::{Float, Int, List};
+    match (func, args) {
+        // Sin argumentos se mantiene el comportamiento historico: Int en 0..100
+        ("random", []) => Int(with_rng(|rng| rng.gen_range(0..100))),
+        ("random", [min, max]) => random(min, max),
+        ("seed", [Int(n)]) => {
+            seed(*n as i64 as u64);
+            VasoType::VBit(1, "".to_string())
+        },
+        ("choice", [List(items)]) if items.is_empty() => err("Math.choice of an empty list".to_string()),
+        ("choice", [List(items)]) => items[with_rng(|rng| rng.gen_range(0..items.len()))].clone(),
+        ("abs", [Int(n)]) => n.checked_abs().map(Int).unwrap_or_else(|| err(format!("Math.abs: {} overflows an Int", n))),
+        ("abs", [Float(x)]) => Float(x.abs()),
+        ("min", _) => extreme(func, args, false),
+        ("max", _) => extreme(func, args, true),
+        ("clamp", [x, lo, hi]) => match (as_f64(x), as_f64(lo), as_f64(hi)) {
+            (Some(_), Some(l), Some(h)) if l > h => err(format!("Math.clamp: low {} is greater than high {}", l, h)),
+            (Some(v), Some(l), _) if v < l => lo.clone(),
+            (Some(v), _, Some(h)) if v > h => hi.clone(),
+            (Some(_), Some(_), Some(_)) => x.clone(),
+            _ => err(format!("Math.clamp needs (value, low, high) numbers, got {:?}", args)),
+        },
+        ("pow", [Int(base), Int(exp)]) if *exp >= 0 => base.checked_pow(*exp as u32).map(Int)
+            .unwrap_or_else(|| err(format!("Math.pow: {}^{} overflows an Int", base, exp))),
+        ("pow", [base, exp]) => match (as_f64(base), as_f64(exp)) {
+            (Some(b), Some(e)) if b == 0.0 && e < 0.0 => err("Math.pow: 0 to a negative power".to_string()),
+            (Some(b), Some(e)) if b < 0.0 && e.fract() != 0.0 => err(format!("Math.pow: negative base {} with fractional exponent {}", b, e)),
+            (Some(b), Some(e)) => float(func, b.powf(e)),
+            _ => err(format!("Math.pow needs (base, exponent) numbers, got {:?}", args)),
+        },
+        ("sqrt", [x]) => match as_f64(x) {
+            Some(v) if v < 0.0 => err(format!("Math.sqrt of negative number {}", x.render())),
+            Some(v) => Float(v.sqrt()),
+            None => err(format!("Math.sqrt needs a number, got {}", x.render())),
+        },
+        ("floor" | "ceil" | "round", [Int(n)]) => Int(*n),
+        ("floor", [Float(x)]) => to_int(func, x.floor()),
+        ("ceil", [Float(x)]) => to_int(func, x.ceil()),
+        ("round", [Float(x)]) => to_int(func, x.round()),
+        // Math.round(ratio, 2) -> Float con 2 decimales
+        ("round", [x, Int(digits)]) if (0..=15).contains(digits) => match as_f64(x) {
+            Some(v) => {
+                let factor = 10f64.powi(*digits);
+                float(func, (v * factor).round() / factor)
+            },
+            None => err(format!("Math.round needs a number, got {}", x.render())),
+        },
+        ("percentile", [List(items), p]) => match as_f64(p) {
+            Some(p) => percentile(items, p),
+            None => err(format!("Math.percentile: p must be a number, got {}", p.render())),
+        },
+        ("stddev", [List(items)]) => stddev(items),
+        (
+            "random" | "seed" | "choice" | "abs" | "clamp" | "pow" | "sqrt" | "floor" | "ceil" | "round"
+            | "percentile" | "stddev",
+            _,
+        ) => err(format!("Math.{} got invalid args {:?}", func, args)),
+        _ => err(format!("Math.{} not found", func)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ints(values: &[i32]) -> Vec<VasoType> {
+        values.iter().map(|n| VasoType::Int(*n)).collect()
+    }
+
+    #[test]
+    fn percentile_interpolates_between_neighbours() {
+        let items = ints(&[4, 1, 3, 2]);
+        assert_eq!(percentile(&items, 50.0), VasoType::Float(2.5));
+        assert_eq!(percentile(&items, 0.0), VasoType::Float(1.0));
+        assert_eq!(percentile(&items, 100.0), VasoType::Float(4.0));
+        assert_eq!(percentile(&items, 25.0), VasoType::Float(1.75));
+        assert_eq!(percentile(&ints(&[7]), 90.0), VasoType::Float(7.0));
+        assert_eq!(percentile(&[VasoType::Int(10), VasoType::Float(20.0)], 50.0), VasoType::Float(15.0));
+    }
+
+    #[test]
+    fn percentile_errors() {
+        assert_eq!(percentile(&[], 50.0), err("Math.percentile of an empty list".to_string()));
+        assert_eq!(percentile(&ints(&[1]), 101.0), err("Math.percentile: p must be between 0 and 100, got 101".to_string()));
+        assert_eq!(percentile(&[VasoType::Str("a".to_string())], 50.0), err("Math.percentile: a is not a number".to_string()));
+    }
+
+    #[test]
+    fn stddev_is_the_population_deviation() {
+        assert_eq!(stddev(&ints(&[2, 4, 4, 4, 5, 5, 7, 9])), VasoType::Float(2.0));
+        assert_eq!(stddev(&ints(&[5])), VasoType::Float(0.0));
+        assert_eq!(stddev(&[]), err("Math.stddev of an empty list".to_string()));
+    }
+
+    #[test]
+    fn seed_repeats_the_same_sequence() {
+        let draw = || (0..5).map(|_| call("random", &[VasoType::Int(1), VasoType::Int(1000)])).collect::<Vec<_>>();
+        seed(42);
+        let first = draw();
+        seed(42);
+        assert_eq!(draw(), first);
+    }
+}
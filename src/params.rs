@@ -0,0 +1,268 @@
+use crate::parser::vbit_literal;
+use crate::tokens::Token;
+use crate::types::VasoType;
+use colored::*;
+use logos::Logos;
+use std::ops::Range;
+
+// --- PARAMETROS DEL SCRIPT ---
+// param env: str = "staging";   // entorno destino
+// param dry: vbit = off;
+// La linea de comandos (--env prod --dry) se valida contra estas declaraciones
+// antes de ejecutar nada. El comentario de la misma linea es la ayuda de --help.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParamType { Str, Int, VBit, Duration, Bytes }
+
+impl ParamType {
+    fn name(&self) -> &'static str {
+        match self {
+            ParamType::Str => "str",
+            ParamType::Int => "int",
+            ParamType::VBit => "vbit",
+            ParamType::Duration => "duration",
+            ParamType::Bytes => "bytes",
+        }
+    }
+
+    // "an int value", "a str value"
+    fn article(&self) -> &'static str {
+        if *self == ParamType::Int { "an" } else { "a" }
+    }
+
+    // Texto de la CLI -> valor tipado, con el mismo lexer que el codigo (10m, 512MiB, on)
+    fn parse(&self, text: &str) -> Option<VasoType> {
+        if *self == ParamType::Str { return Some(VasoType::Str(text.to_string())); }
+        let tokens: Vec<_> = Token::lexer(text).collect();
+        match (self, tokens.as_slice()) {
+            (ParamType::Int, [Ok(Token::NumberLiteral(n))]) => Some(VasoType::Int(*n)),
+            (ParamType::Duration, [Ok(Token::DurationLiteral(ms))]) => Some(VasoType::Duration(*ms)),
+            (ParamType::Bytes, [Ok(Token::BytesLiteral(b))]) => Some(VasoType::Bytes(*b)),
+            (ParamType::VBit, [Ok(t)]) => vbit_literal(t).map(|lvl| VasoType::VBit(lvl, "".to_string())),
+            _ => None,
+        }
+    }
+
+    fn accepts(&self, val: &VasoType) -> bool {
+        matches!((self, val),
+            (ParamType::Str, VasoType::Str(_)) | (ParamType::Int, VasoType::Int(_)) |
+            (ParamType::VBit, VasoType::VBit(..)) | (ParamType::Duration, VasoType::Duration(_)) |
+            (ParamType::Bytes, VasoType::Bytes(_)))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParamDecl {
+    pub name: String,
+    pub ty: ParamType,
+    // None = obligatorio
+    pub default: Option<VasoType>,
+    pub help: String,
+}
+
+// Recorre el script buscando `param nombre: tipo [= literal];`
+pub fn declarations(tokens: &[(Token, Range<usize>)], code: &str) -> Result<Vec<ParamDecl>, String> {
+    let mut decls: Vec<ParamDecl> = Vec::new();
+    for (idx, (token, span)) in tokens.iter().enumerate() {
+        if *token != Token::Param { continue; }
+        let name = match tokens.get(idx + 1) {
+            Some((Token::Identifier(n), _)) => n.clone(),
+            _ => return Err("param expects a name: param env: str = \"staging\";".to_string())
+        };
+        let ty = match (tokens.get(idx + 2), tokens.get(idx + 3)) {
+            (Some((Token::Colon, _)), Some((t, _))) => match t {
+                Token::TypeInt => ParamType::Int,
+                Token::TypeVBit => ParamType::VBit,
+                Token::Identifier(t) if t == "str" => ParamType::Str,
+                Token::Identifier(t) if t == "duration" => ParamType::Duration,
+                Token::Identifier(t) if t == "bytes" => ParamType::Bytes,
+                other => return Err(format!("param '{}': unknown type {:?} (str, int, vbit, duration, bytes)", name, other))
+            },
+            _ => return Err(format!("param '{}' needs a type: param {}: str;", name, name))
+        };
+        let default = match (tokens.get(idx + 4), tokens.get(idx + 5)) {
+            (Some((Token::AssignC | Token::AssignPascal, _)), Some((lit, _))) => {
+                let val = match lit {
+                    Token::StringLiteral(s) => Some(VasoType::Str(s.clone())),
+                    Token::NumberLiteral(n) => Some(VasoType::Int(*n)),
+                    Token::DurationLiteral(ms) => Some(VasoType::Duration(*ms)),
+                    Token::BytesLiteral(b) => Some(VasoType::Bytes(*b)),
+                    t => vbit_literal(t).map(|lvl| VasoType::VBit(lvl, "".to_string())),
+                };
+                match val {
+                    Some(v) if ty.accepts(&v) => Some(v),
+                    _ => return Err(format!("param '{}': default must be a {} literal", name, ty.name()))
+                }
+            },
+            _ => None
+        };
+        if decls.iter().any(|d| d.name == name) {
+            return Err(format!("param '{}' declared twice", name));
+        }
+        // Comentario al final de la misma linea, despues del ';' (un default "https://..." no cuenta)
+        let stmt_end = tokens[idx..].iter().find(|(t, _)| *t == Token::Semicolon).map(|(_, s)| s.end).unwrap_or(span.end);
+        let line_end = code[stmt_end..].find('\n').map(|n| stmt_end + n).unwrap_or(code.len());
+        let help = code[stmt_end..line_end].split_once("//").map(|(_, c)| c.trim().to_string()).unwrap_or_default();
+        decls.push(ParamDecl { name, ty, default, help });
+    }
+    Ok(decls)
+}
+
+pub struct Bound {
+    pub values: Vec<(String, VasoType)>,
+    // Lo que no es flag: Sys.arg(0), Sys.arg(1)...
+    pub positional: Vec<String>,
+}
+
+// --dry-run tambien vale para `param dry_run`
+fn find<'a>(decls: &'a [ParamDecl], key: &str) -> Option<&'a ParamDecl> {
+    decls.iter().find(|d| d.name == key || d.name == key.replace('-', "_"))
+}
+
+// --help / -h solo cuentan en posicion de flag y si el script declara params:
+// en `--msg --help` es el valor de --msg, y sin params todo es posicional
+pub fn wants_help(decls: &[ParamDecl], args: &[String]) -> bool {
+    if decls.is_empty() { return false; }
+    let mut i = 0;
+    while i < args.len() {
+        let arg = &args[i];
+        i += 1;
+        if arg == "--help" || arg == "-h" { return true; }
+        if let Some(decl) = arg.strip_prefix("--").and_then(|flag| find(decls, flag))
+            && decl.ty != ParamType::VBit {
+            i += 1;
+        }
+    }
+    false
+}
+
+// Asocia la linea de comandos a los params.
+// --name valor | --name=valor | --flag (vbit -> on) | --no-flag (vbit -> off)
+pub fn bind(decls: &[ParamDecl], args: &[String]) -> Result<Bound, String> {
+    let mut values: Vec<(String, VasoType)> = Vec::new();
+    let mut positional = Vec::new();
+    let mut i = 0;
+    while i < args.len() {
+        let arg = &args[i];
+        i += 1;
+        // Sin params declarados todo sigue siendo posicional, como antes
+        let Some(flag) = arg.strip_prefix("--").filter(|_| !decls.is_empty()) else {
+            positional.push(arg.clone());
+            continue;
+        };
+        let (key, inline) = match flag.split_once('=') {
+            Some((k, v)) => (k, Some(v.to_string())),
+            None => (flag, None),
+        };
+        let lookup = |k: &str| find(decls, k);
+        let (decl, text) = match (lookup(key), key.strip_prefix("no-").and_then(lookup)) {
+            (Some(d), _) if d.ty == ParamType::VBit && inline.is_none() => (d, "on".to_string()),
+            (Some(d), _) => match inline.or_else(|| { i += 1; args.get(i - 1).cloned() }) {
+                Some(text) => (d, text),
+                None => return Err(format!("--{} expects {} {} value", key, d.ty.article(), d.ty.name()))
+            },
+            (None, Some(d)) if d.ty == ParamType::VBit && inline.is_none() => (d, "off".to_string()),
+            _ => return Err(format!("Unknown option --{}", key))
+        };
+        match decl.ty.parse(&text) {
+            Some(val) => {
+                values.retain(|(n, _)| *n != decl.name);
+                values.push((decl.name.clone(), val));
+            },
+            None => return Err(format!("--{}: '{}' is not a valid {}", key, text, decl.ty.name()))
+        }
+    }
+    for decl in decls {
+        if values.iter().any(|(n, _)| *n == decl.name) { continue; }
+        match &decl.default {
+            Some(val) => values.push((decl.name.clone(), val.clone())),
+            None => return Err(format!("Missing required option --{} <{}>", decl.name, decl.ty.name()))
+        }
+    }
+    Ok(Bound { values, positional })
+}
+
+pub fn print_help(script: &str, decls: &[ParamDecl]) {
+    println!("{} vaso run {} [options] [args...]", "Usage:".bold(), script);
+    println!("\n{}", "Options:".bold());
+    let flags: Vec<String> = decls.iter().map(|d| match d.ty {
+        ParamType::VBit => format!("--{}", d.name),
+        ty => format!("--{} <{}>", d.name, ty.name()),
+    }).collect();
+    let width = flags.iter().map(|f| f.len()).max().unwrap_or(0);
+    for (decl, flag) in decls.iter().zip(flags) {
+        let default = match &decl.default {
+            Some(VasoType::Str(s)) => format!("(default: \"{}\")", s),
+            Some(val) => format!("(default: {})", val.render()),
+            None => "(required)".to_string(),
+        };
+        let help = if decl.help.is_empty() { String::new() } else { format!("{} ", decl.help) };
+        println!("  {}  {}{}", format!("{:<width$}", flag, width = width).cyan(), help, default.dimmed());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decl(name: &str, ty: ParamType, default: Option<VasoType>) -> ParamDecl {
+        ParamDecl { name: name.to_string(), ty, default, help: String::new() }
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|a| a.to_string()).collect()
+    }
+
+    fn value(bound: &Bound, name: &str) -> VasoType {
+        bound.values.iter().find(|(n, _)| n == name).map(|(_, v)| v.clone()).unwrap()
+    }
+
+    fn decls() -> Vec<ParamDecl> {
+        vec![
+            decl("env", ParamType::Str, Some(VasoType::Str("staging".to_string()))),
+            decl("dry_run", ParamType::VBit, Some(VasoType::VBit(0, "".to_string()))),
+            decl("wait", ParamType::Duration, Some(VasoType::Duration(1000))),
+            decl("replicas", ParamType::Int, None),
+        ]
+    }
+
+    #[test]
+    fn binds_every_flag_form() {
+        let bound = bind(&decls(), &args(&["--env", "prod", "--dry-run", "--wait=2m", "--replicas", "3", "extra"])).unwrap();
+        assert_eq!(value(&bound, "env"), VasoType::Str("prod".to_string()));
+        assert_eq!(value(&bound, "dry_run"), VasoType::VBit(1, "".to_string()));
+        assert_eq!(value(&bound, "wait"), VasoType::Duration(120_000));
+        assert_eq!(value(&bound, "replicas"), VasoType::Int(3));
+        assert_eq!(bound.positional, vec!["extra".to_string()]);
+    }
+
+    #[test]
+    fn defaults_and_negated_switches() {
+        let bound = bind(&decls(), &args(&["--replicas=1", "--no-dry-run"])).unwrap();
+        assert_eq!(value(&bound, "env"), VasoType::Str("staging".to_string()));
+        assert_eq!(value(&bound, "dry_run"), VasoType::VBit(0, "".to_string()));
+        assert_eq!(value(&bound, "wait"), VasoType::Duration(1000));
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert_eq!(bind(&decls(), &args(&[])).err().unwrap(), "Missing required option --replicas <int>");
+        assert_eq!(bind(&decls(), &args(&["--replicas", "three"])).err().unwrap(), "--replicas: 'three' is not a valid int");
+        assert_eq!(bind(&decls(), &args(&["--replicas", "1", "--region", "eu"])).err().unwrap(), "Unknown option --region");
+        assert_eq!(bind(&decls(), &args(&["--replicas"])).err().unwrap(), "--replicas expects an int value");
+    }
+
+    #[test]
+    fn without_params_everything_is_positional() {
+        let bound = bind(&[], &args(&["--env", "prod"])).unwrap();
+        assert!(bound.values.is_empty());
+        assert_eq!(bound.positional, args(&["--env", "prod"]));
+    }
+
+    #[test]
+    fn help_only_in_flag_position() {
+        assert!(wants_help(&decls(), &args(&["--replicas", "2", "-h"])));
+        assert!(!wants_help(&decls(), &args(&["--env", "--help"])));
+        assert!(!wants_help(&[], &args(&["--help"])));
+    }
+}
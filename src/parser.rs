@@ -1,10 +1,14 @@
-use crate::types::VasoType;
-use crate::tokens::Token;
+use crate::types::{VasoType, vbit_name};
+use crate::tokens::{Token, StrPart};
+use crate::logic::{apply_op, bool_vbit, check_errors, compare, policy, DEFAULT_POLICY, Policy};
+use crate::stdlib::{call_method, call_std_function};
 use crate::memory::MemoryStack;
+use crate::resilience::{RetryPolicy, Backoff, as_millis};
+use std::collections::BTreeMap;
 use std::ops::Range;
 
-// Actualizado: Ahora acepta tuplas (Token, Range<usize>)
-pub fn extract_args(tokens: &Vec<(Token, Range<usize>)>, start_idx: usize, memory: &MemoryStack) -> (Vec<VasoType>, usize) {
+// Argumentos de una llamada: (expr, expr, ...). Devuelve los valores y el indice tras ')'.
+pub fn extract_args(tokens: &[(Token, Range<usize>)], start_idx: usize, memory: &MemoryStack) -> (Vec<VasoType>, usize) {
     let mut args = Vec::new();
     let mut i = start_idx;
     
@@ -14,20 +18,341 @@ pub fn extract_args(tokens: &Vec<(Token, Range<usize>)>, start_idx: usize, memor
         while i < tokens.len() {
             match &tokens[i] {
                 (Token::RParen, _) => { i += 1; break; },
-                (Token::StringLiteral(s), _) => args.push(VasoType::Str(s.clone())),
-                (Token::NumberLiteral(n), _) => args.push(VasoType::Int(*n)),
-                (Token::Identifier(n), _) => { 
-                    if let Some(val) = memory.get(n) { 
-                        args.push(val.clone()); 
-                    } else {
-                        args.push(VasoType::VBit(3, format!("Var '{}' not found", n)));
+                (Token::Comma, _) => { i += 1; },
+                _ => {
+                    let (val, next) = eval_expr(tokens, i, memory);
+                    args.push(val);
+                    i = next;
+                }
+            }
+        }
+    }
+    (args, i)
+}
+
+// --- EXPRESIONES: comparacion > suma/resta > primario ---
+// Se usa en argumentos, print(...) y dentro de "${...}". Devuelve el valor y el indice siguiente.
+pub fn eval_expr(tokens: &[(Token, Range<usize>)], start_idx: usize, memory: &MemoryStack) -> (VasoType, usize) {
+    let (left, mut i) = eval_sum(tokens, start_idx, memory);
+    if let Some((op @ (Token::LessThan | Token::GreaterThan | Token::Equals | Token::NotEquals), _)) = tokens.get(i) {
+        let (right, next) = eval_sum(tokens, i + 1, memory);
+        i = next;
+        if let Some(err) = check_errors(&[left.clone(), right.clone()]) { return (err, i); }
+        return (bool_vbit(compare(&left, &right, op)), i);
+    }
+    (left, i)
+}
+
+// Condicion de if/while: <expr> [(<|>|==) <expr>]; sin comparacion cuenta un `on`
+// (o un registro con status on). A diferencia de eval_expr, `row == error` compara
+// estados en vez de propagar el error. Devuelve el indice del '{'.
+pub fn eval_condition(tokens: &[(Token, Range<usize>)], start_idx: usize, memory: &MemoryStack) -> (bool, usize) {
+    let (left, i) = eval_sum(tokens, start_idx, memory);
+    if let Some((op @ (Token::LessThan | Token::GreaterThan | Token::Equals | Token::NotEquals), _)) = tokens.get(i) {
+        let (right, next) = eval_sum(tokens, i + 1, memory);
+        return (compare(&left, &right, op), next);
+    }
+    (matches!(left.state(), VasoType::VBit(1, _)), i)
+}
+
+fn eval_sum(tokens: &[(Token, Range<usize>)], start_idx: usize, memory: &MemoryStack) -> (VasoType, usize) {
+    let (mut acc, mut i) = eval_primary(tokens, start_idx, memory);
+    while let Some((op @ (Token::Plus | Token::Minus), _)) = tokens.get(i) {
+        let (right, next) = eval_primary(tokens, i + 1, memory);
+        acc = apply_op(acc, &right, op);
+        i = next;
+    }
+    (acc, i)
+}
+
+// Primario con acceso a campos y metodos: res.stdout, line.trim(), Sys.exec(...).stdout.lines()
+fn eval_primary(tokens: &[(Token, Range<usize>)], i: usize, memory: &MemoryStack) -> (VasoType, usize) {
+    let (mut val, mut j) = eval_atom(tokens, i, memory);
+    while let (Some((Token::Dot, _)), Some(field)) = (tokens.get(j), tokens.get(j + 1).and_then(|(t, _)| member_name(t))) {
+        if let Some((Token::LParen, _)) = tokens.get(j + 2) {
+            // Metodo: server.wait(5s)
+            let (args, next) = extract_args(tokens, j + 2, memory);
+            val = call_method(&val, &field, args);
+            j = next;
+        } else {
+            val = get_field(&val, &field);
+            j += 2;
+        }
+    }
+    (val, j)
+}
+
+fn eval_atom(tokens: &[(Token, Range<usize>)], i: usize, memory: &MemoryStack) -> (VasoType, usize) {
+    match tokens.get(i) {
+        Some((Token::LParen, _)) => {
+            let (val, next) = eval_expr(tokens, i + 1, memory);
+            match tokens.get(next) {
+                Some((Token::RParen, _)) => (val, next + 1),
+                _ => (VasoType::VBit(3, "Expected ')'".to_string()), next)
+            }
+        },
+        Some((Token::LBracket, _)) => {
+            let mut items = Vec::new();
+            let mut j = i + 1;
+            while j < tokens.len() {
+                match &tokens[j] {
+                    (Token::RBracket, _) => { j += 1; break; },
+                    (Token::Comma, _) => { j += 1; },
+                    _ => {
+                        let (val, next) = eval_expr(tokens, j, memory);
+                        items.push(val);
+                        j = next;
+                    }
+                }
+            }
+            (VasoType::List(items), j)
+        },
+        // Modulo.funcion(args)
+        Some((Token::Identifier(module), _)) if matches!(tokens.get(i + 1), Some((Token::Dot, _))) && memory.get(module).is_none() => {
+            let func = tokens.get(i + 2).and_then(|(t, _)| member_name(t));
+            if let (Some(func), Some((Token::LParen, _))) = (func, tokens.get(i + 3)) {
+                let (args, next) = extract_args(tokens, i + 3, memory);
+                (call_std_function(module, &func, args), next)
+            } else {
+                (VasoType::VBit(3, format!("Invalid call on '{}'", module)), i + 2)
+            }
+        },
+        // {clave: expr, ...}
+        Some((Token::LBrace, _)) => {
+            let mut fields = BTreeMap::new();
+            let mut j = i + 1;
+            while j < tokens.len() {
+                match &tokens[j] {
+                    (Token::RBrace, _) => { j += 1; break; },
+                    (Token::Comma, _) => { j += 1; },
+                    (key_token, _) => {
+                        let key = match map_key(key_token) {
+                            Some(k) => k,
+                            None => return (VasoType::VBit(3, format!("Invalid map key {:?}", key_token)), j + 1)
+                        };
+                        if !matches!(tokens.get(j + 1), Some((Token::Colon, _))) {
+                            return (VasoType::VBit(3, format!("Expected ':' after '{}'", key)), j + 1);
+                        }
+                        let (val, next) = eval_expr(tokens, j + 2, memory);
+                        fields.insert(key, val);
+                        j = next;
                     }
-                },
-                (Token::Comma, _) => {},
-                _ => {}
+                }
             }
+            (VasoType::Map(fields), j)
+        },
+        Some((Token::Identifier(n), _)) => match memory.get(n) {
+            Some(val) => (val.clone(), i + 1),
+            None => (VasoType::VBit(3, format!("Var '{}' not found", n)), i + 1)
+        },
+        Some((t, _)) => match token_value(t, memory) {
+            Some(val) => (val, i + 1),
+            None => (VasoType::VBit(3, format!("Unexpected token {:?}", t)), i + 1)
+        },
+        None => (VasoType::VBit(3, "Unexpected end of expression".to_string()), i)
+    }
+}
+
+// Claves de mapa: nombres, strings y numeros ({0: on}); algunas palabras reservadas
+// son claves habituales de opciones ({timeout: 5s}) o de resultados ({error: [...]})
+fn map_key(token: &Token) -> Option<String> {
+    match token {
+        Token::Identifier(k) | Token::StringLiteral(k) => Some(k.clone()),
+        Token::NumberLiteral(n) => Some(n.to_string()),
+        Token::Timeout => Some("timeout".to_string()),
+        Token::Retry => Some("retry".to_string()),
+        Token::Input => Some("input".to_string()),
+        t => vbit_literal(t).map(|lvl| vbit_name(lvl).to_string())
+    }
+}
+
+// Nombre tras un '.': campo, metodo o funcion de modulo. Acepta las mismas palabras que
+// map_key y `match`: report.error, caps.1, Regex.match(...)
+fn member_name(token: &Token) -> Option<String> {
+    match token {
+        Token::Match => Some("match".to_string()),
+        Token::StringLiteral(_) => None,
+        t => map_key(t)
+    }
+}
+
+// Un error se propaga tal cual: Sys.exec fallido -> res.stdout sigue siendo el error
+pub fn get_field(val: &VasoType, field: &str) -> VasoType {
+    match val {
+        VasoType::Map(fields) => fields.get(field).cloned()
+            .unwrap_or_else(|| VasoType::VBit(3, format!("Field '{}' not found", field))),
+        VasoType::Json(serde_json::Value::Object(doc)) => doc.get(field).map(crate::json::to_native)
+            .unwrap_or_else(|| VasoType::VBit(3, format!("Field '{}' not found", field))),
+        VasoType::Proc(id) if field == "pid" => crate::process::proc_pid(*id),
+        VasoType::VBit(3, _) => val.clone(),
+        other => VasoType::VBit(3, format!("Cannot read field '{}' of {}", field, other))
+    }
+}
+
+// "Node ${id} is ${status}"
+fn interpolate(parts: &[StrPart], memory: &MemoryStack) -> String {
+    let mut out = String::new();
+    for part in parts {
+        match part {
+            StrPart::Text(text) => out.push_str(text),
+            StrPart::Expr(expr) => out.push_str(&eval_expr(expr, 0, memory).0.render()),
+        }
+    }
+    out
+}
+
+// Valor simple de un token (literal o variable): asignaciones, condiciones y cabeceras de bloques.
+pub fn token_value(token: &Token, memory: &MemoryStack) -> Option<VasoType> {
+    match token {
+        Token::NumberLiteral(n) => Some(VasoType::Int(*n)),
+        Token::FloatLiteral(x) => Some(VasoType::Float(*x)),
+        Token::StringLiteral(s) => Some(VasoType::Str(s.clone())),
+        Token::TemplateLiteral(parts) => Some(VasoType::Str(interpolate(parts, memory))),
+        Token::DurationLiteral(ms) => Some(VasoType::Duration(*ms)),
+        Token::BytesLiteral(b) => Some(VasoType::Bytes(*b)),
+        Token::Identifier(n) => memory.get(n).cloned(),
+        Token::LitError => Some(VasoType::VBit(3, "Generic Error".to_string())),
+        t => vbit_literal(t).map(|lvl| VasoType::VBit(lvl, "".to_string()))
+    }
+}
+
+pub fn vbit_literal(token: &Token) -> Option<u8> {
+    match token {
+        Token::LitOff => Some(0),
+        Token::LitOn => Some(1),
+        Token::LitLoading => Some(2),
+        Token::LitError => Some(3),
+        Token::LitUnknown => Some(4),
+        _ => None
+    }
+}
+
+// retry <n> [backoff fixed(ms) | linear(step, max: ms) | exponential(base, max: ms)] [jitter] {
+// Devuelve la politica y el indice del '{' del cuerpo.
+pub fn parse_retry_header(tokens: &[(Token, Range<usize>)], start_idx: usize, memory: &MemoryStack) -> Result<(RetryPolicy, usize), String> {
+    let mut i = start_idx + 1;
+    let attempts = match tokens.get(i).and_then(|(t, _)| token_value(t, memory)) {
+        Some(VasoType::Int(n)) if n > 0 => n as u32,
+        _ => return Err("retry expects a positive attempt count".to_string())
+    };
+    let mut policy = RetryPolicy::new(attempts);
+    i += 1;
+
+    if let Some((Token::Backoff, _)) = tokens.get(i) {
+        let kind = match tokens.get(i + 1) {
+            Some((Token::Identifier(k), _)) => k.clone(),
+            _ => return Err("backoff expects fixed, linear or exponential".to_string())
+        };
+        i += 2;
+        let mut positional = Vec::new();
+        let mut max = None;
+        if let Some((Token::LParen, _)) = tokens.get(i) {
             i += 1;
+            while i < tokens.len() {
+                match &tokens[i] {
+                    (Token::RParen, _) => { i += 1; break; },
+                    (Token::Identifier(name), _) if name == "max" && matches!(tokens.get(i + 1), Some((Token::Colon, _))) => {
+                        let val = tokens.get(i + 2).and_then(|(t, _)| token_value(t, memory));
+                        max = Some(val.as_ref().and_then(as_millis).ok_or("backoff max expects milliseconds")?);
+                        i += 3;
+                        continue;
+                    },
+                    (Token::Comma, _) => {},
+                    (t, _) => {
+                        let val = token_value(t, memory);
+                        positional.push(val.as_ref().and_then(as_millis).ok_or("backoff expects milliseconds")?);
+                    }
+                }
+                i += 1;
+            }
         }
+        let first = positional.first().copied();
+        policy.backoff = match (kind.as_str(), first) {
+            ("fixed", Some(ms)) => Backoff::Fixed(ms),
+            ("linear", Some(step)) => Backoff::Linear { step, max },
+            ("exponential", Some(base)) => Backoff::Exponential { base, max },
+            _ => return Err(format!("Unknown backoff '{}'", kind))
+        };
     }
-    (args, i)
-}
\ No newline at end of file
+
+    if let Some((Token::Jitter, _)) = tokens.get(i) {
+        policy.jitter = true;
+        i += 1;
+    }
+
+    match tokens.get(i) {
+        Some((Token::LBrace, _)) => Ok((policy, i)),
+        _ => Err("retry expects a '{' block".to_string())
+    }
+}
+
+// timeout <expr> {   -> (milisegundos, indice del '{')
+// La cabecera es una expresion completa: timeout limit {  /  timeout (base + 5s) {
+pub fn parse_timeout_header(tokens: &[(Token, Range<usize>)], start_idx: usize, memory: &MemoryStack) -> Result<(u64, usize), String> {
+    let (val, i) = eval_expr(tokens, start_idx + 1, memory);
+    if let VasoType::VBit(3, msg) = val { return Err(msg); }
+    let ms = as_millis(&val).ok_or("timeout expects milliseconds")?;
+    match tokens.get(i) {
+        Some((Token::LBrace, _)) => Ok((ms, i)),
+        _ => Err("timeout expects a '{' block".to_string())
+    }
+}
+
+// with_lock <ruta> [timeout <ms>] {
+// Devuelve la ruta del lock, la espera maxima y el indice del '{' del cuerpo.
+pub fn parse_lock_header(tokens: &[(Token, Range<usize>)], start_idx: usize, memory: &MemoryStack) -> Result<(String, Option<u64>, usize), String> {
+    let (path, mut i) = eval_expr(tokens, start_idx + 1, memory);
+    let path = match path {
+        VasoType::Str(p) => p,
+        VasoType::VBit(3, msg) => return Err(msg),
+        other => return Err(format!("with_lock expects a lock file path, got {}", other))
+    };
+    let mut wait = None;
+    if let Some((Token::Timeout, _)) = tokens.get(i) {
+        let (val, next) = eval_expr(tokens, i + 1, memory);
+        if let VasoType::VBit(3, msg) = val { return Err(msg); }
+        wait = Some(as_millis(&val).ok_or("with_lock timeout expects a duration")?);
+        i = next;
+    }
+    match tokens.get(i) {
+        Some((Token::LBrace, _)) => Ok((path, wait, i)),
+        _ => Err("with_lock expects a '{' block".to_string())
+    }
+}
+
+// policy default | <s> > <s> > <s> > <s> > <s> [absorbing off] | absorbing off   ( ';' o '{' )
+// -> (politica, indice del ';' o del '{')
+pub fn parse_policy_header(tokens: &[(Token, Range<usize>)], start_idx: usize) -> Result<(Policy, usize), String> {
+    let mut i = start_idx + 1;
+    let mut current = policy();
+    if let Some((Token::Identifier(word), _)) = tokens.get(i)
+        && word == "default" {
+        current = DEFAULT_POLICY;
+        i += 1;
+    }
+    let mut order = Vec::new();
+    while let Some(level) = tokens.get(i).and_then(|(t, _)| vbit_literal(t)) {
+        if order.contains(&level) { return Err(format!("policy lists '{}' twice", vbit_name(level))); }
+        order.push(level);
+        i += 1;
+        if let Some((Token::GreaterThan, _)) = tokens.get(i) { i += 1; } else { break; }
+    }
+    match order.len() {
+        0 => {},
+        // Un orden completo es una declaracion nueva: off solo es absorbente si se repite
+        5 => current = Policy { order: [order[0], order[1], order[2], order[3], order[4]], off_absorbing: false },
+        n => return Err(format!("policy must rank all five states, got {}", n)),
+    }
+    if let Some((Token::Identifier(word), _)) = tokens.get(i)
+        && word == "absorbing" {
+        match tokens.get(i + 1) {
+            Some((Token::LitOff, _)) => current.off_absorbing = true,
+            _ => return Err("only 'off' can be absorbing: policy ... absorbing off".to_string())
+        }
+        i += 2;
+    }
+    match tokens.get(i) {
+        Some((Token::Semicolon | Token::LBrace, _)) => Ok((current, i)),
+        _ => Err("policy expects 'error > ... > off' followed by ';' or a '{' block".to_string())
+    }
+}
@@ -0,0 +1,171 @@
+use crate::types::VasoType;
+use std::cell::RefCell;
+use std::path::{Component, Path, PathBuf};
+
+// --- RUTAS: Path.join / parent / normalize / relative_to ... ---
+// Todo es lexico (no toca el disco ni resuelve symlinks), salvo `absolute`,
+// que parte del directorio actual.
+
+thread_local! {
+    static SCRIPT_PATH: RefCell<PathBuf> = const { RefCell::new(PathBuf::new()) };
+}
+
+pub fn set_script_path(path: &str) {
+    SCRIPT_PATH.with(|p| *p.borrow_mut() = PathBuf::from(path));
+}
+
+fn text(path: &Path) -> VasoType {
+    VasoType::Str(path.to_string_lossy().to_string())
+}
+
+fn missing(what: &str, path: &str) -> VasoType {
+    VasoType::VBit(4, format!("'{}' has no {}", path, what))
+}
+
+// Path.join("dist", "js/", "app.js") -> dist/js/app.js. Una parte absoluta reemplaza a las anteriores.
+pub fn join(parts: &[String]) -> VasoType {
+    let mut path = PathBuf::new();
+    for part in parts { path.push(part); }
+    text(&path)
+}
+
+// Como dirname: "file.txt" -> "."
+pub fn parent(path: &str) -> VasoType {
+    match Path::new(path).parent() {
+        Some(p) if p.as_os_str().is_empty() => VasoType::Str(".".to_string()),
+        Some(p) => text(p),
+        None => missing("parent", path),
+    }
+}
+
+pub fn file_name(path: &str) -> VasoType {
+    match Path::new(path).file_name() {
+        Some(name) => VasoType::Str(name.to_string_lossy().to_string()),
+        None => missing("file name", path),
+    }
+}
+
+pub fn stem(path: &str) -> VasoType {
+    match Path::new(path).file_stem() {
+        Some(stem) => VasoType::Str(stem.to_string_lossy().to_string()),
+        None => missing("file name", path),
+    }
+}
+
+pub fn extension(path: &str) -> VasoType {
+    match Path::new(path).extension() {
+        Some(ext) => VasoType::Str(ext.to_string_lossy().to_string()),
+        None => missing("extension", path),
+    }
+}
+
+// with_extension("app.ts", "js") -> app.js; "" quita la extension
+pub fn with_extension(path: &str, ext: &str) -> VasoType {
+    text(&Path::new(path).with_extension(ext.trim_start_matches('.')))
+}
+
+// Quita "." y resuelve ".." sin mirar el disco: a/./b/../c -> a/c
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for comp in path.components() {
+        match comp {
+            Component::CurDir => {},
+            Component::ParentDir => match out.components().next_back() {
+                Some(Component::Normal(_)) => { out.pop(); },
+                // /.. sigue siendo /
+                Some(Component::RootDir) | Some(Component::Prefix(_)) => {},
+                _ => out.push(".."),
+            },
+            other => out.push(other.as_os_str()),
+        }
+    }
+    if out.as_os_str().is_empty() { out.push("."); }
+    out
+}
+
+pub fn normalize(path: &str) -> VasoType {
+    text(&normalize_path(Path::new(path)))
+}
+
+fn absolute_path(path: &Path) -> std::io::Result<PathBuf> {
+    std::path::absolute(path).map(|p| normalize_path(&p))
+}
+
+pub fn absolute(path: &str) -> VasoType {
+    match absolute_path(Path::new(path)) {
+        Ok(p) => text(&p),
+        Err(e) => VasoType::VBit(3, format!("Path.absolute: '{}': {}", path, e)),
+    }
+}
+
+// relative_to("/app/dist/js/a.js", "/app/src") -> ../dist/js/a.js
+pub fn relative_to(path: &str, base: &str) -> VasoType {
+    let (target, base_abs) = match (absolute_path(Path::new(path)), absolute_path(Path::new(base))) {
+        (Ok(t), Ok(b)) => (t, b),
+        (Err(e), _) | (_, Err(e)) => return VasoType::VBit(3, format!("Path.relative_to: {}", e)),
+    };
+    let t: Vec<Component> = target.components().collect();
+    let b: Vec<Component> = base_abs.components().collect();
+    // Distinta unidad en Windows (C: vs D:): no hay camino relativo
+    if t.first() != b.first() {
+        return VasoType::VBit(3, format!("Path.relative_to: '{}' and '{}' have different roots", path, base));
+    }
+    let common = t.iter().zip(b.iter()).take_while(|(x, y)| x == y).count();
+    let mut out = PathBuf::new();
+    for _ in common..b.len() { out.push(".."); }
+    for comp in &t[common..] { out.push(comp.as_os_str()); }
+    if out.as_os_str().is_empty() { out.push("."); }
+    text(&out)
+}
+
+// Directorio del .vs que se esta ejecutando, sin importar desde donde se lanzo
+pub fn script_dir() -> VasoType {
+    let script = SCRIPT_PATH.with(|p| p.borrow().clone());
+    match absolute_path(&script) {
+        Ok(p) => text(p.parent().unwrap_or(&p)),
+        Err(e) => VasoType::VBit(3, format!("Path.script_dir: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(text: &str) -> VasoType {
+        VasoType::Str(text.to_string())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn normalize_is_lexical() {
+        assert_eq!(normalize("a/./b/../c"), s("a/c"));
+        assert_eq!(normalize("a/b/../../.."), s(".."));
+        assert_eq!(normalize("../x/../y"), s("../y"));
+        assert_eq!(normalize("/../etc//hosts"), s("/etc/hosts"));
+        assert_eq!(normalize("./"), s("."));
+        assert_eq!(normalize("a/.."), s("."));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn relative_to_walks_up_from_the_base() {
+        assert_eq!(relative_to("/app/dist/js/a.js", "/app/src"), s("../dist/js/a.js"));
+        assert_eq!(relative_to("/app/src/main.rs", "/app"), s("src/main.rs"));
+        assert_eq!(relative_to("/app", "/app/src/lib"), s("../.."));
+        assert_eq!(relative_to("/app/./src/../dist", "/app/dist/"), s("."));
+    }
+
+    #[test]
+    fn relative_paths_start_from_the_current_directory() {
+        assert_eq!(relative_to("dist/app.js", "."), s(&Path::new("dist").join("app.js").to_string_lossy()));
+        assert_eq!(relative_to("src", "src/lib"), s(".."));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn parent_of_a_bare_file_is_the_current_directory() {
+        assert_eq!(parent("file.txt"), s("."));
+        assert_eq!(parent("dist/app.js"), s("dist"));
+        assert!(matches!(parent("/"), VasoType::VBit(4, _)));
+    }
+}
@@ -0,0 +1,102 @@
+use crate::types::VasoType;
+use crate::logic::bool_vbit;
+use regex::Regex;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+
+// --- REGEX: Regex.match / captures / find_all / replace ---
+// Cada patron se compila una sola vez: la cache va por texto del patron, asi que una
+// llamada dentro de un for (mismo sitio, mismo literal) no recompila en cada vuelta.
+// Un patron invalido es un Error con el mensaje del compilador, nunca un panic.
+
+const CACHE_LIMIT: usize = 256;
+
+thread_local! {
+    static CACHE: RefCell<HashMap<String, Result<Regex, String>>> = RefCell::new(HashMap::new());
+}
+
+// El error de regex viene en varias lineas (patron, flecha, "error: ..."); nos quedamos con la causa
+fn compile_message(e: &regex::Error) -> String {
+    let text = e.to_string();
+    match text.rfind("error: ") {
+        Some(pos) => text[pos + 7..].trim().to_string(),
+        None => text.split_whitespace().collect::<Vec<_>>().join(" "),
+    }
+}
+
+pub fn compile(pattern: &str) -> Result<Regex, String> {
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if let Some(found) = cache.get(pattern) { return found.clone(); }
+        // Patrones generados en bucle no deben crecer sin limite
+        if cache.len() >= CACHE_LIMIT { cache.clear(); }
+        let compiled = Regex::new(pattern).map_err(|e| format!("Regex: invalid pattern '{}': {}", pattern, compile_message(&e)));
+        cache.insert(pattern.to_string(), compiled.clone());
+        compiled
+    })
+}
+
+// Grupos por posicion ("0" es el match completo) y por nombre; un grupo que no participa es unknown
+fn captures(re: &Regex, text: &str) -> VasoType {
+    let Some(caps) = re.captures(text) else { return VasoType::VBit(0, "".to_string()); };
+    let group = |m: Option<regex::Match>| m.map(|m| VasoType::Str(m.as_str().to_string())).unwrap_or(VasoType::VBit(4, "".to_string()));
+    let mut fields = BTreeMap::new();
+    for (i, name) in re.capture_names().enumerate() {
+        fields.insert(i.to_string(), group(caps.get(i)));
+        if let Some(name) = name { fields.insert(name.to_string(), group(caps.get(i))); }
+    }
+    VasoType::Map(fields)
+}
+
+pub fn call(func: &str, args: &[VasoType]) -> VasoType {
+    use VasoType::Str;
+    let (pattern, text) = match (args.first(), args.get(1)) {
+        (Some(Str(p)), Some(Str(t))) => (p, t),
+        _ => return VasoType::VBit(3, format!("Regex.{} needs (pattern, text), got {:?}", func, args)),
+    };
+    let re = match compile(pattern) {
+        Ok(re) => re,
+        Err(e) => return VasoType::VBit(3, e),
+    };
+    match (func, args.get(2)) {
+        ("match", None) => bool_vbit(re.is_match(text)),
+        ("captures", None) => captures(&re, text),
+        ("find_all", None) => VasoType::List(re.find_iter(text).map(|m| Str(m.as_str().to_string())).collect()),
+        // $1 / $name en el reemplazo (en un string de Vaso: "$1" o r"${name}")
+        ("replace", Some(Str(with))) => Str(re.replace_all(text, with.as_str()).to_string()),
+        ("match" | "captures" | "find_all" | "replace", _) => VasoType::VBit(3, format!("Regex.{} got invalid args {:?}", func, args)),
+        _ => VasoType::VBit(3, format!("Regex.{} not found", func)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(v: &str) -> VasoType { VasoType::Str(v.to_string()) }
+
+    #[test]
+    fn captures_by_position_and_name() {
+        let caps = call("captures", &[s(r"(?P<host>[a-z]+)-(\d+)?x"), s("web-x")]);
+        match caps {
+            VasoType::Map(fields) => {
+                assert_eq!(fields["0"], s("web-x"));
+                assert_eq!(fields["host"], s("web"));
+                assert_eq!(fields["2"], VasoType::VBit(4, "".to_string()));
+            },
+            other => panic!("expected a map, got {:?}", other),
+        }
+        assert_eq!(call("captures", &[s(r"\d+"), s("none")]), VasoType::VBit(0, "".to_string()));
+    }
+
+    #[test]
+    fn replace_and_find_all() {
+        assert_eq!(call("replace", &[s(r"(\w+)@(\w+)"), s("a@b c@d"), s("$2@$1")]), s("b@a d@c"));
+        assert_eq!(call("find_all", &[s(r"\d+"), s("1 22 333")]), VasoType::List(vec![s("1"), s("22"), s("333")]));
+    }
+
+    #[test]
+    fn invalid_pattern_is_an_error() {
+        assert_eq!(call("match", &[s("(bad"), s("x")]), VasoType::VBit(3, "Regex: invalid pattern '(bad': unclosed group".to_string()));
+    }
+}
@@ -0,0 +1,731 @@
+use crate::resilience::{as_millis, deadline_expired, nearest_deadline, pop_deadline, push_deadline, timeout_error};
+use crate::environ;
+use crate::types::VasoType;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use colored::*;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdout, Command, ExitStatus, Output, Stdio};
+use std::sync::{Arc, Mutex, MutexGuard};
+#[cfg(unix)]
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// Modo streaming: cada linea se reenvia al terminal en cuanto llega con un prefijo
+// de etapa ("[build] ..."), y opcionalmente se copia a un log.
+#[derive(Clone)]
+pub struct LineSink {
+    pub prefix: String,
+    pub log: Option<Arc<Mutex<File>>>,
+}
+
+impl LineSink {
+    fn emit(&self, line: &str, is_err: bool) {
+        let tag = format!("[{}]", self.prefix);
+        if is_err { eprintln!("{} {}", tag.red(), line); } else { println!("{} {}", tag.cyan(), line); }
+        if let Some(log) = &self.log
+            && let Ok(mut file) = log.lock() {
+            let _ = writeln!(file, "{} {}", tag, line);
+        }
+    }
+}
+
+// --- OPCIONES DE EJECUCION ({cwd, env, stdin, timeout, codes, stream, log}) ---
+
+pub struct ExecOptions {
+    pub stdin: Option<String>,
+    pub codes: ExitCodeMap,
+    pub timeout: Option<u64>,
+    pub sink: Option<LineSink>,
+}
+
+// Comando directo (sin shell) con cwd y env ya aplicados
+pub fn new_command(cmd: &str, argv: &[String], opts: &BTreeMap<String, VasoType>) -> Result<Command, String> {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut c = Command::new("cmd");
+        c.arg("/C").arg(cmd).args(argv);
+        c
+    } else {
+        let mut c = Command::new(cmd);
+        c.args(argv);
+        c
+    };
+    if let Some(cwd) = opts.get("cwd") { command.current_dir(cwd.render()); }
+    // Primero lo de Env.set/Env.load, despues lo propio de esta llamada ({env: {CI: "1", DEBUG: unknown}})
+    for (key, val) in environ::overlay() {
+        match val { Some(v) => command.env(key, v), None => command.env_remove(key) };
+    }
+    match opts.get("env") {
+        Some(VasoType::Map(vars)) => for (key, val) in vars {
+            match val {
+                VasoType::VBit(4, _) => command.env_remove(key),
+                val => command.env(key, val.render()),
+            };
+        },
+        Some(_) => return Err("env must be a map".to_string()),
+        None => {}
+    }
+    Ok(command)
+}
+
+pub fn exec_options(cmd: &str, opts: &BTreeMap<String, VasoType>) -> Result<ExecOptions, String> {
+    let stdin = opts.get("stdin").map(|s| s.render());
+    let codes = match opts.get("codes") {
+        Some(rules) => ExitCodeMap::from_value(rules)?,
+        None => exit_codes()
+    };
+    let timeout = match opts.get("timeout") {
+        Some(t) => Some(as_millis(t).ok_or("timeout must be a Duration")?),
+        None => None
+    };
+
+    // stream: "build" (prefijo) u on (prefijo = comando); log: "build.log" (se anexa)
+    let prefix = match opts.get("stream") {
+        None | Some(VasoType::VBit(0, _)) => None,
+        Some(VasoType::VBit(1, _)) => Some(cmd.rsplit(['/', '\\']).next().unwrap_or(cmd).to_string()),
+        Some(VasoType::Str(prefix)) => Some(prefix.clone()),
+        Some(other) => return Err(format!("stream must be on/off or a prefix, got {}", other))
+    };
+    let sink = match (prefix, opts.get("log")) {
+        (Some(prefix), log) => {
+            let log = match log {
+                Some(path) => match OpenOptions::new().create(true).append(true).open(path.render()) {
+                    Ok(file) => Some(Arc::new(Mutex::new(file))),
+                    Err(e) => return Err(format!("cannot open log '{}': {}", path.render(), e))
+                },
+                None => None
+            };
+            Some(LineSink { prefix, log })
+        },
+        (None, Some(_)) => return Err("log needs stream".to_string()),
+        (None, None) => None
+    };
+    Ok(ExecOptions { stdin, codes, timeout, sink })
+}
+
+// Sys.sh("cat $1 | wc -l", [file], {opts}): /bin/sh -c con los argumentos como $1, $2...
+// (nunca interpolados en el script, asi no hace falta citarlos)
+pub fn sh(script: &str, argv: &[String], opts: &BTreeMap<String, VasoType>) -> VasoType {
+    let shell = if cfg!(unix) { "/bin/sh" } else { "sh" };
+    let mut sh_args = vec!["-c".to_string(), script.to_string(), "sh".to_string()];
+    sh_args.extend_from_slice(argv);
+    match (new_command(shell, &sh_args, opts), exec_options("sh", opts)) {
+        (Ok(command), Ok(options)) => run_with(vec![command], options, "sh"),
+        (Err(e), _) | (_, Err(e)) => VasoType::VBit(3, format!("Sys.sh: {}", e))
+    }
+}
+
+// Cita un valor para pegarlo en un script de shell: it's -> 'it'\''s'
+pub fn shell_quote(text: &str) -> String {
+    if !text.is_empty() && text.chars().all(|c| c.is_ascii_alphanumeric() || "-_./=:@%+,".contains(c)) {
+        return text.to_string();
+    }
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+// Sys.exec(cmd, [args], {opts}) -> {status, code, stdout, stderr, duration}
+pub fn exec(cmd: &str, argv: &[String], opts: &BTreeMap<String, VasoType>) -> VasoType {
+    match (new_command(cmd, argv, opts), exec_options(cmd, opts)) {
+        (Ok(command), Ok(options)) => run_with(vec![command], options, cmd),
+        (Err(e), _) | (_, Err(e)) => VasoType::VBit(3, format!("Sys.exec: {}", e))
+    }
+}
+
+// Sys.pipe([["cat", "access.log"], ["grep", "500"]], {opts}): etapas conectadas sin shell
+pub fn pipe(stages: &[Vec<String>], opts: &BTreeMap<String, VasoType>) -> VasoType {
+    let mut commands = Vec::new();
+    for stage in stages {
+        let Some((cmd, argv)) = stage.split_first() else {
+            return VasoType::VBit(3, "Sys.pipe: empty stage".to_string());
+        };
+        match new_command(cmd, argv, opts) {
+            Ok(command) => commands.push(command),
+            Err(e) => return VasoType::VBit(3, format!("Sys.pipe: {}", e))
+        }
+    }
+    let label: Vec<&str> = stages.iter().map(|s| s[0].as_str()).collect();
+    match exec_options(label[0], opts) {
+        Ok(options) => run_with(commands, options, &label.join(" | ")),
+        Err(e) => VasoType::VBit(3, format!("Sys.pipe: {}", e))
+    }
+}
+
+// Ejecuta y arma el registro. `label` identifica el comando en el error de arranque.
+pub fn run_with(commands: Vec<Command>, options: ExecOptions, label: &str) -> VasoType {
+    // El timeout propio se apila como un deadline mas: run_pipeline ya sabe matar al vencer
+    let start = Instant::now();
+    if let Some(ms) = options.timeout { push_deadline(start + Duration::from_millis(ms)); }
+    let result = run_pipeline(commands, options.stdin, options.sink);
+    if options.timeout.is_some() { pop_deadline(); }
+    let elapsed = start.elapsed().as_millis() as u64;
+
+    match result {
+        Ok((statuses, stdout, stderr)) => {
+            // Como `set -o pipefail`: manda la ultima etapa que fallo
+            let status = statuses.iter().rev().find(|s| !s.success()).unwrap_or(&statuses[statuses.len() - 1]);
+            let output = Output { status: *status, stdout, stderr };
+            let mut record = exec_record(&output, elapsed, &options.codes);
+            if let VasoType::Map(fields) = &mut record {
+                if let Some(ms) = options.timeout
+                    && elapsed >= ms && output.status.code().is_none() {
+                    fields.insert("status".to_string(), timeout_error(ms));
+                }
+                if statuses.len() > 1 {
+                    let codes = statuses.iter().map(|s| s.code().map(VasoType::Int).unwrap_or(VasoType::VBit(4, "".to_string()))).collect();
+                    fields.insert("stages".to_string(), VasoType::List(codes));
+                }
+            }
+            record
+        },
+        Err(e) => VasoType::VBit(3, format!("Exec Error: {}: {}", label, e))
+    }
+}
+
+// Ejecuta N etapas (una para Sys.exec) capturando la salida, con stdout de cada una -> stdin
+// de la siguiente. Si hay un timeout activo, todas (y sus grupos) se matan al vencer el deadline.
+// Devuelve el estado de cada etapa, el stdout de la ultima y el stderr de todas.
+pub fn run_pipeline(commands: Vec<Command>, stdin: Option<String>, sink: Option<LineSink>) -> std::io::Result<(Vec<ExitStatus>, Vec<u8>, Vec<u8>)> {
+    // Solo aislamos el grupo de procesos si hay un timeout que pueda necesitar matarlo;
+    // asi Ctrl-C sigue llegando a los hijos en el caso normal.
+    #[cfg(unix)]
+    let isolate = nearest_deadline().is_some();
+    let last = commands.len() - 1;
+    let mut children: Vec<Child> = Vec::new();
+    let mut err_readers = Vec::new();
+    let mut prev_out: Option<ChildStdout> = None;
+    for (n, mut command) in commands.into_iter().enumerate() {
+        match prev_out.take() {
+            Some(out) => command.stdin(Stdio::from(out)),
+            None => command.stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() }),
+        };
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+        #[cfg(unix)]
+        if isolate {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                for started in children.iter_mut() { kill_tree(started); let _ = started.wait(); }
+                return Err(e);
+            }
+        };
+        // Leemos los pipes en hilos para que el hijo nunca se bloquee con el buffer lleno
+        if let Some(pipe) = child.stderr.take() { err_readers.push(read_pipe(pipe, sink.clone(), true)); }
+        if n < last { prev_out = child.stdout.take(); }
+        children.push(child);
+    }
+
+    // El stdin tambien va en un hilo: un hijo que no lee no debe bloquearnos
+    if let (Some(input), Some(mut pipe)) = (stdin, children[0].stdin.take()) {
+        thread::spawn(move || { let _ = pipe.write_all(input.as_bytes()); });
+    }
+    let out_reader = children[last].stdout.take().map(|pipe| read_pipe(pipe, sink, false));
+
+    let mut statuses: Vec<Option<ExitStatus>> = vec![None; children.len()];
+    loop {
+        for (child, status) in children.iter_mut().zip(statuses.iter_mut()) {
+            if status.is_none() { *status = child.try_wait()?; }
+        }
+        if statuses.iter().all(Option::is_some) { break; }
+        if deadline_expired() {
+            for (child, status) in children.iter_mut().zip(statuses.iter_mut()) {
+                if status.is_none() {
+                    kill_tree(child);
+                    *status = Some(child.wait()?);
+                }
+            }
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let stdout = out_reader.and_then(|h| h.join().ok()).unwrap_or_default();
+    let stderr = err_readers.into_iter().flat_map(|h| h.join().unwrap_or_default()).collect();
+    Ok((statuses.into_iter().flatten().collect(), stdout, stderr))
+}
+
+// Captura todo el pipe; con sink, ademas reenvia linea a linea mientras llega
+fn read_pipe<R: Read + Send + 'static>(pipe: R, sink: Option<LineSink>, is_err: bool) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let buf = Mutex::new(Vec::new());
+        pump(pipe, sink.as_ref(), is_err, &buf);
+        buf.into_inner().unwrap_or_default()
+    })
+}
+
+// Lee hasta EOF dejando cada linea en `buf` en cuanto llega (Proc lee el buffer en vivo)
+fn pump<R: Read>(pipe: R, sink: Option<&LineSink>, is_err: bool, buf: &Mutex<Vec<u8>>) {
+    let mut reader = BufReader::new(pipe);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        if let Some(sink) = sink {
+            let text = String::from_utf8_lossy(&line);
+            sink.emit(text.trim_end_matches(['\n', '\r']), is_err);
+        }
+        if let Ok(mut out) = buf.lock() { out.extend_from_slice(&line); }
+    }
+}
+
+// Mata al hijo y a los procesos que haya lanzado (npm -> node, sh -> ...)
+pub fn kill_tree(child: &mut Child) {
+    #[cfg(unix)]
+    unsafe {
+        // process_group(0) hace que el pgid sea igual al pid del hijo
+        libc::kill(-(child.id() as i32), libc::SIGKILL);
+    }
+    let _ = child.kill();
+}
+
+// --- PROCESOS EN SEGUNDO PLANO (Proc.spawn) ---
+// El handle es un indice a este registro. Todo lo que siga vivo al terminar el
+// script (o al recibir Ctrl-C / TERM, ver install_signal_cleanup) se mata en cleanup_background().
+
+struct Background {
+    child: Child,
+    stdout: Arc<Mutex<Vec<u8>>>,
+    stderr: Arc<Mutex<Vec<u8>>>,
+    // Hilos que vacian stdout/stderr; wait() los espera antes de armar el registro
+    readers: Vec<JoinHandle<()>>,
+    started: Instant,
+    codes: ExitCodeMap,
+    // Estado y duracion cuando ya termino
+    finished: Option<(ExitStatus, u64)>,
+}
+
+impl Background {
+    fn poll(&mut self) {
+        if self.finished.is_none()
+            && let Ok(Some(status)) = self.child.try_wait() {
+            self.finished = Some((status, self.started.elapsed().as_millis() as u64));
+        }
+    }
+
+    fn output(&self, status: ExitStatus) -> Output {
+        let stdout = self.stdout.lock().map(|b| b.clone()).unwrap_or_default();
+        let stderr = self.stderr.lock().map(|b| b.clone()).unwrap_or_default();
+        Output { status, stdout, stderr }
+    }
+}
+
+// Global (no thread_local): el hilo de señales tambien tiene que limpiarlo
+static BACKGROUND: Mutex<Vec<Background>> = Mutex::new(Vec::new());
+
+fn background() -> MutexGuard<'static, Vec<Background>> {
+    BACKGROUND.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// Proc.spawn(cmd, [args], {cwd, env, stdin, codes, stream, log}) -> handle
+pub fn spawn(cmd: &str, argv: &[String], opts: &BTreeMap<String, VasoType>) -> VasoType {
+    let (mut command, options) = match (new_command(cmd, argv, opts), exec_options(cmd, opts)) {
+        (Ok(command), Ok(options)) => (command, options),
+        (Err(e), _) | (_, Err(e)) => return VasoType::VBit(3, format!("Proc.spawn: {}", e))
+    };
+    if options.timeout.is_some() {
+        return VasoType::VBit(3, "Proc.spawn: use wait(timeout) instead of a timeout option".to_string());
+    }
+    command.stdin(if options.stdin.is_some() { Stdio::piped() } else { Stdio::null() });
+    command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    // Grupo propio: kill() llega tambien a los nietos (npm -> node)
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => return VasoType::VBit(3, format!("Exec Error: {}: {}", cmd, e))
+    };
+    if let (Some(input), Some(mut pipe)) = (options.stdin, child.stdin.take()) {
+        thread::spawn(move || { let _ = pipe.write_all(input.as_bytes()); });
+    }
+    let stdout = Arc::new(Mutex::new(Vec::new()));
+    let stderr = Arc::new(Mutex::new(Vec::new()));
+    let mut readers = Vec::new();
+    for (is_err, buf) in [(false, &stdout), (true, &stderr)] {
+        let buf = Arc::clone(buf);
+        let sink = options.sink.clone();
+        let pipe: Option<Box<dyn Read + Send>> = if is_err {
+            child.stderr.take().map(|p| Box::new(p) as Box<dyn Read + Send>)
+        } else {
+            child.stdout.take().map(|p| Box::new(p) as Box<dyn Read + Send>)
+        };
+        if let Some(pipe) = pipe {
+            readers.push(thread::spawn(move || pump(pipe, sink.as_ref(), is_err, &buf)));
+        }
+    }
+    let bg = Background { child, stdout, stderr, readers, started: Instant::now(), codes: options.codes, finished: None };
+    let mut procs = background();
+    procs.push(bg);
+    VasoType::Proc(procs.len() - 1)
+}
+
+fn with_proc<T>(id: usize, f: impl FnOnce(&mut Background) -> T) -> Option<T> {
+    background().get_mut(id).map(f)
+}
+
+pub fn proc_pid(id: usize) -> VasoType {
+    with_proc(id, |bg| VasoType::Int(bg.child.id() as i32)).unwrap_or(VasoType::VBit(3, "Invalid process handle".to_string()))
+}
+
+// loading mientras corre; al terminar, el estado que digan las reglas de codigos
+pub fn proc_status(id: usize) -> VasoType {
+    with_proc(id, |bg| {
+        bg.poll();
+        match bg.finished {
+            None => VasoType::VBit(2, "running".to_string()),
+            Some((status, ms)) => match exec_record(&bg.output(status), ms, &bg.codes) {
+                VasoType::Map(mut fields) => fields.remove("status").unwrap_or(VasoType::VBit(4, "".to_string())),
+                other => other
+            }
+        }
+    }).unwrap_or(VasoType::VBit(3, "Invalid process handle".to_string()))
+}
+
+// Espera al proceso (como mucho `timeout_ms`, y nunca mas alla de un deadline activo).
+// Termina -> el mismo registro que Sys.exec; sigue vivo -> loading.
+pub fn proc_wait(id: usize, timeout_ms: Option<u64>) -> VasoType {
+    let start = Instant::now();
+    loop {
+        let done = with_proc(id, |bg| {
+            bg.poll();
+            bg.finished.map(|_| std::mem::take(&mut bg.readers))
+        });
+        match done {
+            None => return VasoType::VBit(3, "Invalid process handle".to_string()),
+            Some(Some(readers)) => {
+                // Los lectores terminan al llegar a EOF: despues de join la salida esta completa.
+                // Se espera fuera del lock para no bloquear al hilo de señales.
+                for reader in readers { let _ = reader.join(); }
+                return with_proc(id, |bg| {
+                    let (status, ms) = bg.finished.unwrap();
+                    exec_record(&bg.output(status), ms, &bg.codes)
+                }).unwrap_or(VasoType::VBit(3, "Invalid process handle".to_string()));
+            },
+            Some(None) => {}
+        }
+        let waited = start.elapsed().as_millis() as u64;
+        if timeout_ms.is_some_and(|ms| waited >= ms) || deadline_expired() {
+            return VasoType::VBit(2, format!("Still running after {}", VasoType::Duration(waited)));
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+pub fn proc_output(id: usize, is_err: bool) -> VasoType {
+    with_proc(id, |bg| {
+        let buf = if is_err { &bg.stderr } else { &bg.stdout };
+        VasoType::Str(String::from_utf8_lossy(&buf.lock().map(|b| b.clone()).unwrap_or_default()).to_string())
+    }).unwrap_or(VasoType::VBit(3, "Invalid process handle".to_string()))
+}
+
+// kill(h) manda TERM; kill(h, "KILL") / kill(h, 9). En Windows siempre es un kill duro.
+pub fn proc_kill(id: usize, signal: &VasoType) -> VasoType {
+    let sig = match signal_number(signal) {
+        Some(sig) => sig,
+        None => return VasoType::VBit(3, format!("Unknown signal {}", signal))
+    };
+    with_proc(id, |bg| {
+        bg.poll();
+        if bg.finished.is_some() { return VasoType::VBit(1, "".to_string()); }
+        send_signal(&mut bg.child, sig)
+    }).unwrap_or(VasoType::VBit(3, "Invalid process handle".to_string()))
+}
+
+#[cfg(unix)]
+fn signal_number(signal: &VasoType) -> Option<i32> {
+    match signal {
+        VasoType::Int(n) if *n > 0 => Some(*n),
+        VasoType::Str(name) => match name.trim_start_matches("SIG") {
+            "TERM" => Some(libc::SIGTERM),
+            "KILL" => Some(libc::SIGKILL),
+            "INT" => Some(libc::SIGINT),
+            "HUP" => Some(libc::SIGHUP),
+            "QUIT" => Some(libc::SIGQUIT),
+            "USR1" => Some(libc::SIGUSR1),
+            "USR2" => Some(libc::SIGUSR2),
+            _ => None
+        },
+        _ => None
+    }
+}
+
+#[cfg(not(unix))]
+fn signal_number(signal: &VasoType) -> Option<i32> {
+    match signal {
+        VasoType::Int(n) if *n > 0 => Some(*n),
+        VasoType::Str(_) => Some(9),
+        _ => None
+    }
+}
+
+fn send_signal(child: &mut Child, sig: i32) -> VasoType {
+    #[cfg(unix)]
+    {
+        // Al grupo entero (pgid = pid del hijo)
+        if unsafe { libc::kill(-(child.id() as i32), sig) } == 0 { return VasoType::VBit(1, "".to_string()); }
+        VasoType::VBit(3, format!("Kill Error: {}", std::io::Error::last_os_error()))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = sig;
+        match child.kill() {
+            Ok(_) => VasoType::VBit(1, "".to_string()),
+            Err(e) => VasoType::VBit(3, format!("Kill Error: {}", e))
+        }
+    }
+}
+
+// Al terminar el script: TERM a todo lo que siga vivo, 2s de gracia y despues KILL
+pub fn cleanup_background() {
+    let mut procs = background();
+    procs.iter_mut().for_each(Background::poll);
+    let mut alive: Vec<&mut Background> = procs.iter_mut().filter(|bg| bg.finished.is_none()).collect();
+    if alive.is_empty() { return; }
+    #[cfg(unix)]
+    for bg in alive.iter_mut() { send_signal(&mut bg.child, libc::SIGTERM); }
+    let grace = Instant::now() + Duration::from_secs(2);
+    while Instant::now() < grace && alive.iter_mut().any(|bg| { bg.poll(); bg.finished.is_none() }) {
+        thread::sleep(Duration::from_millis(20));
+    }
+    for bg in alive.iter_mut().filter(|bg| bg.finished.is_none()) {
+        kill_tree(&mut bg.child);
+        let _ = bg.child.wait();
+    }
+}
+
+// --- SEÑALES: Ctrl-C / kill <pid de vaso> ---
+// Los hijos de Proc.spawn tienen su propio grupo (para que kill() alcance a los nietos),
+// asi que Ctrl-C no les llega. El handler solo escribe el numero de señal en un pipe
+// (write es async-signal-safe); un hilo lo lee, limpia y sale con 128 + señal, como una shell.
+#[cfg(unix)]
+static SIGNAL_PIPE: AtomicI32 = AtomicI32::new(-1);
+
+#[cfg(unix)]
+extern "C" fn on_signal(sig: libc::c_int) {
+    let byte = sig as u8;
+    unsafe { libc::write(SIGNAL_PIPE.load(Ordering::Relaxed), (&byte as *const u8).cast(), 1); }
+}
+
+#[cfg(unix)]
+pub fn install_signal_cleanup() {
+    let mut fds = [0; 2];
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 { return; }
+    // Los hijos no deben heredar el pipe
+    for fd in fds { unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC); } }
+    SIGNAL_PIPE.store(fds[1], Ordering::Relaxed);
+    thread::spawn(move || {
+        let mut byte = 0u8;
+        loop {
+            let n = unsafe { libc::read(fds[0], (&mut byte as *mut u8).cast(), 1) };
+            if n == 1 { break; }
+            if n < 0 && std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted { continue; }
+            return;
+        }
+        eprintln!("{} {}", "❌ INTERRUPTED:".red().bold(), format!("signal {}, stopping background processes", byte).white());
+        cleanup_background();
+        std::process::exit(128 + byte as i32);
+    });
+    for sig in [libc::SIGINT, libc::SIGTERM, libc::SIGHUP] {
+        unsafe { libc::signal(sig, on_signal as *const () as libc::sighandler_t); }
+    }
+}
+
+// Windows: Ctrl-C llega a toda la consola, hijos incluidos
+#[cfg(not(unix))]
+pub fn install_signal_cleanup() {}
+
+// --- CODIGOS DE SALIDA -> VBIT ---
+// {0: on, 1: off, 75: loading, _: error}. Un fallo temporal como `loading` entra
+// directo en la logica de retry en vez de ser un error duro.
+
+#[derive(Debug, Clone)]
+pub struct ExitCodeMap {
+    rules: Vec<(i32, u8)>,
+    fallback: u8,
+}
+
+impl Default for ExitCodeMap {
+    fn default() -> Self {
+        ExitCodeMap { rules: vec![(0, 1)], fallback: 3 }
+    }
+}
+
+impl ExitCodeMap {
+    pub fn preset(name: &str) -> Option<Self> {
+        let rules = match name {
+            "default" => vec![(0, 1)],
+            // grep: 1 = sin coincidencias; diff: 1 = hay diferencias
+            "grep" | "diff" => vec![(0, 1), (1, 0)],
+            // sysexits.h: EX_UNAVAILABLE (69) y EX_TEMPFAIL (75) son reintentables
+            "sysexits" => vec![(0, 1), (69, 2), (75, 2)],
+            _ => return None,
+        };
+        Some(ExitCodeMap { rules, fallback: 3 })
+    }
+
+    // Acepta un preset ("grep") o un mapa {codigo: vbit, _: vbit}
+    pub fn from_value(val: &VasoType) -> Result<Self, String> {
+        let fields = match val {
+            VasoType::Str(name) => return Self::preset(name).ok_or(format!("Unknown exit code preset '{}'", name)),
+            VasoType::Map(fields) => fields,
+            other => return Err(format!("Exit code rules must be a map or a preset, got {}", other)),
+        };
+        let mut map = ExitCodeMap { rules: Vec::new(), fallback: 3 };
+        for (key, state) in fields {
+            let level = match state {
+                VasoType::VBit(lvl, _) => *lvl,
+                other => return Err(format!("Exit code {} must map to a vbit, got {}", key, other)),
+            };
+            if key == "_" {
+                map.fallback = level;
+            } else {
+                let code = key.parse().map_err(|_| format!("Invalid exit code '{}'", key))?;
+                map.rules.push((code, level));
+            }
+        }
+        // Si las reglas no dicen nada del 0, sigue siendo exito
+        if !map.rules.iter().any(|(code, _)| *code == 0) { map.rules.push((0, 1)); }
+        Ok(map)
+    }
+
+    // Sin codigo (muerto por una senal) siempre es error
+    pub fn level(&self, code: Option<i32>) -> u8 {
+        match code {
+            Some(c) => self.rules.iter().find(|(rc, _)| *rc == c).map(|(_, lvl)| *lvl).unwrap_or(self.fallback),
+            None => 3,
+        }
+    }
+}
+
+thread_local! {
+    static EXIT_CODES: RefCell<ExitCodeMap> = RefCell::new(ExitCodeMap::default());
+}
+
+// Sys.exit_codes(...): reglas globales para todos los Sys.exec sin `codes`
+pub fn set_exit_codes(map: ExitCodeMap) {
+    EXIT_CODES.with(|m| *m.borrow_mut() = map);
+}
+
+pub fn exit_codes() -> ExitCodeMap {
+    EXIT_CODES.with(|m| m.borrow().clone())
+}
+
+// Resultado de Sys.exec: {status, code, stdout, stderr, duration}.
+// `status` sale de las reglas de codigos; fuera de `on` lleva el codigo y el final
+// de stderr (o stdout) como mensaje.
+pub fn exec_record(output: &Output, elapsed_ms: u64, codes: &ExitCodeMap) -> VasoType {
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let code = output.status.code();
+    let status = match codes.level(code) {
+        1 => VasoType::VBit(1, "".to_string()),
+        level => {
+            let detail = if stderr.trim().is_empty() { &stdout } else { &stderr };
+            let exit = code.map(|c| c.to_string()).unwrap_or_else(|| signal_name(&output.status));
+            let msg = if level == 3 { format!("CMD Failed (exit {})", exit) } else { format!("exit {}", exit) };
+            let detail = tail(detail, 20);
+            VasoType::VBit(level, if detail.is_empty() { msg } else { format!("{}: {}", msg, detail) })
+        }
+    };
+    let mut fields = BTreeMap::new();
+    fields.insert("status".to_string(), status);
+    // Sin codigo = muerto por una senal (timeout, kill)
+    fields.insert("code".to_string(), code.map(VasoType::Int).unwrap_or(VasoType::VBit(4, "".to_string())));
+    fields.insert("stdout".to_string(), VasoType::Str(stdout));
+    fields.insert("stderr".to_string(), VasoType::Str(stderr));
+    fields.insert("duration".to_string(), VasoType::Duration(elapsed_ms));
+    VasoType::Map(fields)
+}
+
+// "signal 15" para un proceso que no llego a devolver codigo
+fn signal_name(status: &ExitStatus) -> String {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(sig) = status.signal() { return format!("signal {}", sig); }
+    }
+    let _ = status;
+    "signal".to_string()
+}
+
+// Ultimas `n` lineas no vacias: suficiente contexto para el mensaje de error
+pub fn tail(text: &str, n: usize) -> String {
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    lines[lines.len().saturating_sub(n)..].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vbit(level: u8) -> VasoType {
+        VasoType::VBit(level, "".to_string())
+    }
+
+    fn rules(pairs: &[(&str, VasoType)]) -> VasoType {
+        VasoType::Map(pairs.iter().map(|(k, v)| (k.to_string(), v.clone())).collect())
+    }
+
+    #[test]
+    fn map_with_fallback() {
+        let map = ExitCodeMap::from_value(&rules(&[("1", vbit(0)), ("75", vbit(2)), ("_", vbit(4))])).unwrap();
+        assert_eq!(map.level(Some(0)), 1);
+        assert_eq!(map.level(Some(1)), 0);
+        assert_eq!(map.level(Some(75)), 2);
+        assert_eq!(map.level(Some(2)), 4);
+        // Muerto por una senal: siempre error
+        assert_eq!(map.level(None), 3);
+    }
+
+    #[test]
+    fn zero_can_be_remapped() {
+        let map = ExitCodeMap::from_value(&rules(&[("0", vbit(0))])).unwrap();
+        assert_eq!(map.level(Some(0)), 0);
+        assert_eq!(map.level(Some(3)), 3);
+    }
+
+    #[test]
+    fn presets() {
+        let grep = ExitCodeMap::from_value(&VasoType::Str("grep".to_string())).unwrap();
+        assert_eq!((grep.level(Some(0)), grep.level(Some(1)), grep.level(Some(2))), (1, 0, 3));
+        let sysexits = ExitCodeMap::from_value(&VasoType::Str("sysexits".to_string())).unwrap();
+        assert_eq!((sysexits.level(Some(69)), sysexits.level(Some(75)), sysexits.level(Some(64))), (2, 2, 3));
+    }
+
+    #[test]
+    fn invalid_rules() {
+        assert_eq!(ExitCodeMap::from_value(&VasoType::Str("bash".to_string())).unwrap_err(), "Unknown exit code preset 'bash'");
+        assert_eq!(ExitCodeMap::from_value(&rules(&[("x", vbit(0))])).unwrap_err(), "Invalid exit code 'x'");
+        assert!(ExitCodeMap::from_value(&rules(&[("1", VasoType::Int(0))])).unwrap_err().starts_with("Exit code 1 must map to a vbit"));
+        assert!(ExitCodeMap::from_value(&VasoType::Int(1)).is_err());
+    }
+
+    #[test]
+    fn shell_quote_only_when_needed() {
+        assert_eq!(shell_quote("dist/app.js"), "dist/app.js");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote("a b"), "'a b'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn wait_returns_output_larger_than_a_pipe_buffer() {
+        let argv = ["-c".to_string(), "head -c 200000 /dev/zero | tr '\\0' x".to_string()];
+        let VasoType::Proc(id) = spawn("sh", &argv, &BTreeMap::new()) else { panic!() };
+        let VasoType::Map(record) = proc_wait(id, None) else { panic!() };
+        match record.get("stdout") {
+            Some(VasoType::Str(out)) => assert!(out.len() == 200_000 && out.bytes().all(|b| b == b'x'), "{}", out.len()),
+            other => panic!("{:?}", other),
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use crate::types::{VasoType, vbit_name};
+use crate::numbers;
+use rand::Rng;
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+// --- POLITICAS DE REINTENTO (retry N backoff ... { } until ...) ---
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Backoff {
+    None,
+    Fixed(u64),
+    Linear { step: u64, max: Option<u64> },
+    Exponential { base: u64, max: Option<u64> },
+}
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub backoff: Backoff,
+    pub jitter: bool,
+    // Estado que detiene el retry (`until on`). None = parar en todo menos error/loading.
+    pub until: Option<u8>,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32) -> Self {
+        RetryPolicy { max_attempts, backoff: Backoff::None, jitter: false, until: None }
+    }
+
+    // Espera (ms) antes del siguiente intento, despues de fallar el intento `attempt` (1-based).
+    pub fn delay_ms(&self, attempt: u32) -> u64 {
+        let exp = attempt.saturating_sub(1).min(32);
+        let raw = match &self.backoff {
+            Backoff::None => 0,
+            Backoff::Fixed(ms) => *ms,
+            Backoff::Linear { step, max } => cap(step.saturating_mul(attempt as u64), *max),
+            Backoff::Exponential { base, max } => cap(base.saturating_mul(1u64 << exp), *max),
+        };
+        if self.jitter && raw > 1 {
+            // "Equal jitter": la mitad fija + la otra mitad aleatoria
+            let half = raw / 2;
+            half + numbers::with_rng(|rng| rng.gen_range(0..=raw - half))
+        } else {
+            raw
+        }
+    }
+
+    // El resultado del bloque decide: error y loading se reintentan, el resto detiene.
+    pub fn should_retry(&self, result: &VasoType) -> bool {
+        let level = state_of(result);
+        match self.until {
+            Some(stop) => level != stop,
+            None => matches!(level, 2 | 3),
+        }
+    }
+}
+
+fn cap(ms: u64, max: Option<u64>) -> u64 {
+    match max { Some(m) => ms.min(m), None => ms }
+}
+
+// Cualquier valor que no sea VBit cuenta como exito (on)
+pub fn state_of(val: &VasoType) -> u8 {
+    match val.state() {
+        VasoType::VBit(lvl, _) => *lvl,
+        _ => 1,
+    }
+}
+
+// Acepta una Duration (5s) o milisegundos como Int.
+pub fn as_millis(val: &VasoType) -> Option<u64> {
+    match val {
+        VasoType::Duration(ms) => Some(*ms),
+        VasoType::Int(n) if *n >= 0 => Some(*n as u64),
+        _ => None,
+    }
+}
+
+pub fn describe_failure(val: &VasoType) -> String {
+    match val.state() {
+        VasoType::VBit(3, msg) if !msg.is_empty() => msg.clone(),
+        VasoType::VBit(lvl, msg) if msg.is_empty() => vbit_name(*lvl).to_string(),
+        VasoType::VBit(lvl, msg) => format!("{}: {}", vbit_name(*lvl), msg),
+        other => other.to_string(),
+    }
+}
+
+// Valor final cuando se agotan los intentos: un error con el historial completo.
+pub fn exhausted(failures: &[String]) -> VasoType {
+    let history: Vec<String> = failures.iter().enumerate()
+        .map(|(n, msg)| format!("#{} {}", n + 1, msg))
+        .collect();
+    VasoType::VBit(3, format!("Retry exhausted after {} attempts: {}", failures.len(), history.join(" | ")))
+}
+
+// --- TIMEOUTS (timeout ms { } y --timeout) ---
+// Pila de deadlines activos. Las operaciones bloqueantes (sleep, exec) nunca esperan
+// mas alla del deadline mas cercano, asi el interprete puede cancelar el bloque.
+
+thread_local! {
+    static DEADLINES: RefCell<Vec<Instant>> = const { RefCell::new(Vec::new()) };
+}
+
+pub fn push_deadline(deadline: Instant) {
+    DEADLINES.with(|d| d.borrow_mut().push(deadline));
+}
+
+pub fn pop_deadline() {
+    DEADLINES.with(|d| { d.borrow_mut().pop(); });
+}
+
+pub fn nearest_deadline() -> Option<Instant> {
+    DEADLINES.with(|d| d.borrow().iter().min().copied())
+}
+
+pub fn deadline_expired() -> bool {
+    nearest_deadline().map(|d| Instant::now() >= d).unwrap_or(false)
+}
+
+// Tiempo restante hasta el deadline mas cercano (None = sin limite)
+pub fn remaining() -> Option<Duration> {
+    nearest_deadline().map(|d| d.saturating_duration_since(Instant::now()))
+}
+
+// 30000 -> "30s", 90000 -> "1m30s"
+pub fn format_millis(ms: u64) -> String {
+    VasoType::Duration(ms).to_string()
+}
+
+pub fn timeout_error(ms: u64) -> VasoType {
+    VasoType::VBit(3, format!("Timeout after {}", format_millis(ms)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(backoff: Backoff) -> RetryPolicy {
+        RetryPolicy { backoff, ..RetryPolicy::new(5) }
+    }
+
+    #[test]
+    fn backoff_curves() {
+        let waits = |p: &RetryPolicy| (1..=5).map(|a| p.delay_ms(a)).collect::<Vec<_>>();
+        assert_eq!(waits(&RetryPolicy::new(5)), vec![0, 0, 0, 0, 0]);
+        assert_eq!(waits(&policy(Backoff::Fixed(500))), vec![500, 500, 500, 500, 500]);
+        assert_eq!(waits(&policy(Backoff::Linear { step: 100, max: Some(250) })), vec![100, 200, 250, 250, 250]);
+        assert_eq!(waits(&policy(Backoff::Exponential { base: 200, max: None })), vec![200, 400, 800, 1600, 3200]);
+        assert_eq!(waits(&policy(Backoff::Exponential { base: 200, max: Some(1000) })), vec![200, 400, 800, 1000, 1000]);
+    }
+
+    #[test]
+    fn exponential_saturates_instead_of_overflowing() {
+        let p = policy(Backoff::Exponential { base: u64::MAX / 2, max: None });
+        assert_eq!(p.delay_ms(100), u64::MAX);
+    }
+
+    #[test]
+    fn jitter_stays_between_half_and_full_wait() {
+        let p = RetryPolicy { jitter: true, ..policy(Backoff::Fixed(1000)) };
+        for attempt in 1..=50 {
+            let wait = p.delay_ms(attempt);
+            assert!((500..=1000).contains(&wait), "{}", wait);
+        }
+    }
+
+    #[test]
+    fn seeded_jitter_is_reproducible() {
+        let p = RetryPolicy { jitter: true, ..policy(Backoff::Fixed(1000)) };
+        numbers::seed(7);
+        let first: Vec<u64> = (1..=5).map(|a| p.delay_ms(a)).collect();
+        numbers::seed(7);
+        assert_eq!((1..=5).map(|a| p.delay_ms(a)).collect::<Vec<_>>(), first);
+    }
+}
@@ -0,0 +1,176 @@
+use crate::types::VasoType;
+use crate::patterns;
+use serde_json::{Map, Value};
+
+// --- JSON SCHEMA (subconjunto practico) ---
+// type, enum, const, required, properties, additionalProperties, items,
+// minimum/maximum (y exclusive*), minLength/maxLength, pattern, minItems/maxItems.
+// Se recorren todas las reglas y se acumulan las violaciones con su JSON pointer.
+
+// RFC 6901: "/spec/containers/0/image"; "~" y "/" dentro de una clave se escapan
+fn child(pointer: &str, key: &str) -> String {
+    format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"))
+}
+
+fn shown(pointer: &str) -> &str {
+    if pointer.is_empty() { "/" } else { pointer }
+}
+
+fn type_name(v: &Value) -> &'static str {
+    match v {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn type_matches(v: &Value, expected: &str) -> bool {
+    let actual = type_name(v);
+    actual == expected || (expected == "number" && actual == "integer")
+        // 2.0 cuenta como entero, igual que en JSON Schema
+        || (expected == "integer" && v.as_f64().is_some_and(|f| f.fract() == 0.0))
+}
+
+fn number(schema: &Map<String, Value>, key: &str) -> Option<f64> {
+    schema.get(key).and_then(Value::as_f64)
+}
+
+fn check(doc: &Value, schema: &Value, pointer: &str, errors: &mut Vec<String>) {
+    let schema = match schema {
+        Value::Object(s) => s,
+        Value::Bool(true) => return,
+        Value::Bool(false) => { errors.push(format!("{}: not allowed", shown(pointer))); return; },
+        _ => { errors.push(format!("{}: schema must be an object", shown(pointer))); return; }
+    };
+    let mut fail = |msg: String| errors.push(format!("{}: {}", shown(pointer), msg));
+
+    if let Some(expected) = schema.get("type") {
+        let names: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        };
+        if !names.iter().any(|t| type_matches(doc, t)) {
+            // Con el tipo equivocado el resto de reglas solo generaria ruido
+            fail(format!("expected {}, got {}", names.join(" or "), type_name(doc)));
+            return;
+        }
+    }
+    if let Some(Value::Array(options)) = schema.get("enum")
+        && !options.contains(doc) {
+        let list: Vec<String> = options.iter().map(Value::to_string).collect();
+        fail(format!("{} is not one of [{}]", doc, list.join(", ")));
+    }
+    if let Some(expected) = schema.get("const")
+        && expected != doc {
+        fail(format!("expected {}, got {}", expected, doc));
+    }
+
+    match doc {
+        Value::Number(n) => {
+            let x = n.as_f64().unwrap_or(f64::NAN);
+            if let Some(min) = number(schema, "minimum") && x < min { fail(format!("{} is less than minimum {}", n, min)); }
+            if let Some(max) = number(schema, "maximum") && x > max { fail(format!("{} is greater than maximum {}", n, max)); }
+            if let Some(min) = number(schema, "exclusiveMinimum") && x <= min { fail(format!("{} must be greater than {}", n, min)); }
+            if let Some(max) = number(schema, "exclusiveMaximum") && x >= max { fail(format!("{} must be less than {}", n, max)); }
+        },
+        Value::String(s) => {
+            let len = s.chars().count() as f64;
+            if let Some(min) = number(schema, "minLength") && len < min { fail(format!("shorter than {} characters", min)); }
+            if let Some(max) = number(schema, "maxLength") && len > max { fail(format!("longer than {} characters", max)); }
+            if let Some(Value::String(pattern)) = schema.get("pattern") {
+                match patterns::compile(pattern) {
+                    Ok(re) if !re.is_match(s) => fail(format!("\"{}\" does not match pattern '{}'", s, pattern)),
+                    Ok(_) => {},
+                    Err(e) => fail(e),
+                }
+            }
+        },
+        Value::Array(items) => {
+            let len = items.len() as f64;
+            if let Some(min) = number(schema, "minItems") && len < min { fail(format!("has {} items, needs at least {}", len, min)); }
+            if let Some(max) = number(schema, "maxItems") && len > max { fail(format!("has {} items, allows at most {}", len, max)); }
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    check(item, item_schema, &child(pointer, &i.to_string()), errors);
+                }
+            }
+        },
+        Value::Object(fields) => {
+            if let Some(Value::Array(required)) = schema.get("required") {
+                for key in required.iter().filter_map(Value::as_str) {
+                    if !fields.contains_key(key) {
+                        errors.push(format!("{}: required property missing", child(pointer, key)));
+                    }
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (key, value) in fields {
+                let path = child(pointer, key);
+                match (properties.and_then(|p| p.get(key)), schema.get("additionalProperties")) {
+                    (Some(prop_schema), _) => check(value, prop_schema, &path, errors),
+                    (None, Some(extra)) => check(value, extra, &path, errors),
+                    (None, None) => {},
+                }
+            }
+        },
+        _ => {}
+    }
+}
+
+// Json.validate(doc, schema) -> on, o un error con todas las violaciones
+pub fn validate(doc: &Value, schema: &Value) -> VasoType {
+    let mut errors = Vec::new();
+    check(doc, schema, "", &mut errors);
+    match errors.len() {
+        0 => VasoType::VBit(1, "".to_string()),
+        1 => VasoType::VBit(3, format!("Schema violation: {}", errors[0])),
+        n => VasoType::VBit(3, format!("{} schema violations: {}", n, errors.join(" | "))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn violations(doc: Value, schema: Value) -> String {
+        match validate(&doc, &schema) {
+            VasoType::VBit(1, _) => String::new(),
+            VasoType::VBit(_, msg) => msg,
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn collects_every_violation_with_its_pointer() {
+        let schema = json!({
+            "type": "object",
+            "required": ["name", "spec"],
+            "properties": {"spec": {"properties": {"replicas": {"type": "integer", "minimum": 1}}}}
+        });
+        assert_eq!(violations(json!({"name": "api", "spec": {"replicas": 2}}), schema.clone()), "");
+        assert_eq!(
+            violations(json!({"spec": {"replicas": 0}}), schema),
+            "2 schema violations: /name: required property missing | /spec/replicas: 0 is less than minimum 1"
+        );
+    }
+
+    #[test]
+    fn wrong_type_reports_only_the_mismatch() {
+        assert_eq!(violations(json!("3"), json!({"type": "integer", "minimum": 5})), "Schema violation: /: expected integer, got string");
+        assert_eq!(violations(json!(2.0), json!({"type": "integer"})), "");
+    }
+
+    #[test]
+    fn items_pattern_and_additional_properties() {
+        let schema = json!({"items": {"pattern": "^v[0-9]+$"}, "maxItems": 2});
+        assert_eq!(violations(json!(["v1", "x"]), schema), "Schema violation: /1: \"x\" does not match pattern '^v[0-9]+$'");
+        let closed = json!({"properties": {"a/b": {}}, "additionalProperties": false});
+        assert_eq!(violations(json!({"a/b": 1, "c": 2}), closed), "Schema violation: /c: not allowed");
+    }
+}
@@ -2,109 +2,261 @@ use crate::types::VasoType;
 use crate::logic::check_errors;
 use std::fs;
 use std::env;
-use std::process::Command;
-use std::time::{SystemTime, UNIX_EPOCH, Duration}; // <--- Agregamos Duration
+use std::cell::RefCell;
+use crate::process::{self, set_exit_codes, ExitCodeMap};
+use crate::resilience::{remaining, as_millis};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use crate::datetime;
+use crate::environ;
+use crate::files;
+use crate::formats;
+use crate::tables::{self, CsvOptions};
+use crate::json;
+use crate::schema;
+use crate::locks;
+use crate::paths;
+use crate::strings;
+use crate::patterns;
+use crate::lists;
+use crate::vbits;
+use crate::numbers;
 use std::thread; // <--- Agregamos thread para sleep
-use rand::Rng;
-use serde_json::Value;
+
+thread_local! {
+    static SCRIPT_ARGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+// Argumentos posicionales del script (lo que sigue a script.vs en la linea de comandos)
+pub fn set_script_args(args: Vec<String>) {
+    SCRIPT_ARGS.with(|a| *a.borrow_mut() = args);
+}
+
+// Espera compartida por Time.sleep y los backoff de retry.
+// Nunca duerme mas alla de un timeout activo: el interprete cancela el bloque al despertar.
+pub fn sleep_ms(ms: u64) {
+    let wanted = Duration::from_millis(ms);
+    let wait = match remaining() { Some(left) => wanted.min(left), None => wanted };
+    thread::sleep(wait);
+}
 
 pub fn call_std_function(module: &str, func: &str, args: Vec<VasoType>) -> VasoType {
-    if let Some(err) = check_errors(&args) { return err; }
+    // VBit trabaja con estados: un `error` es un argumento valido (VBit.count(xs, error)).
+    // List tambien: un error puede ser un elemento (xs.push(error)); lists::call decide.
+    if !matches!(module, "VBit" | "List") && let Some(err) = check_errors(&args) { return err; }
 
     match module {
         "Time" => match func {
-            "now" => {
-                let start = SystemTime::now();
-                let since = start.duration_since(UNIX_EPOCH).expect("Time fail");
-                VasoType::Int(since.as_secs() as i32)
-            },
-            "sleep" => { // <--- NUEVO: Time.sleep(ms)
-                if let Some(VasoType::Int(ms)) = args.get(0) {
-                    thread::sleep(Duration::from_millis(*ms as u64));
+            "now" => VasoType::DateTime(datetime::now_utc()),
+            "unix" => match args.first() { // Time.unix(t) -> segundos epoch
+                Some(VasoType::DateTime(t)) => VasoType::Int(t.timestamp() as i32),
+                None => VasoType::Int(datetime::now_utc().timestamp() as i32),
+                _ => VasoType::VBit(3, "Time.unix needs (DateTime)".to_string())
+            },
+            "from_unix" => match args.first() {
+                Some(VasoType::Int(secs)) => datetime::from_unix(*secs as i64).map(VasoType::DateTime)
+                    .unwrap_or(VasoType::VBit(3, format!("Invalid unix timestamp {}", secs))),
+                _ => VasoType::VBit(3, "Time.from_unix needs (Int)".to_string())
+            },
+            "parse" => match (args.first(), args.get(1)) { // Time.parse(s) RFC 3339 / Time.parse(s, "%d/%m/%Y %H:%M")
+                (Some(VasoType::Str(text)), None) => datetime::parse(text, None),
+                (Some(VasoType::Str(text)), Some(VasoType::Str(fmt))) => datetime::parse(text, Some(fmt)),
+                _ => VasoType::VBit(3, "Time.parse needs (Str) or (Str, format)".to_string())
+            },
+            "format" => match (args.first(), args.get(1)) {
+                (Some(VasoType::DateTime(t)), Some(VasoType::Str(fmt))) => datetime::format(t, fmt),
+                _ => VasoType::VBit(3, "Time.format needs (DateTime, Str)".to_string())
+            },
+            "rfc3339" => match args.first() {
+                Some(VasoType::DateTime(t)) => VasoType::Str(datetime::to_rfc3339(t)),
+                _ => VasoType::VBit(3, "Time.rfc3339 needs (DateTime)".to_string())
+            },
+            "utc" => match args.first() {
+                Some(VasoType::DateTime(t)) => VasoType::DateTime(t.to_utc().fixed_offset()),
+                _ => VasoType::VBit(3, "Time.utc needs (DateTime)".to_string())
+            },
+            "with_offset" => match (args.first(), args.get(1)) { // mismo instante, otro huso: "+02:00"
+                (Some(VasoType::DateTime(t)), Some(VasoType::Str(off))) => match datetime::parse_offset(off) {
+                    Some(offset) => VasoType::DateTime(t.with_timezone(&offset)),
+                    None => VasoType::VBit(3, format!("Invalid UTC offset '{}'", off))
+                },
+                _ => VasoType::VBit(3, "Time.with_offset needs (DateTime, Str)".to_string())
+            },
+            "offset" => match args.first() { // "+02:00"
+                Some(VasoType::DateTime(t)) => VasoType::Str(t.offset().to_string()),
+                _ => VasoType::VBit(3, "Time.offset needs (DateTime)".to_string())
+            },
+            "monotonic" => VasoType::Duration(datetime::monotonic_ms()),
+            "since" => match args.first() { // Duration transcurrida desde un DateTime o una lectura de Time.monotonic()
+                Some(VasoType::DateTime(t)) => {
+                    let ms = (datetime::now_utc() - *t).num_milliseconds();
+                    if ms >= 0 { VasoType::Duration(ms as u64) } else { VasoType::VBit(3, "Time.since: date is in the future".to_string()) }
+                },
+                Some(VasoType::Duration(start)) => VasoType::Duration(datetime::monotonic_ms().saturating_sub(*start)),
+                _ => VasoType::VBit(3, "Time.since needs (DateTime) or (Duration from Time.monotonic)".to_string())
+            },
+            "sleep" => { // <--- NUEVO: Time.sleep(ms) / Time.sleep(250ms)
+                if let Some(ms) = args.first().and_then(as_millis) {
+                    sleep_ms(ms);
                     VasoType::VBit(1, "".to_string())
-                } else { VasoType::VBit(3, "Arg Error: Sleep needs Int (ms)".to_string()) }
+                } else { VasoType::VBit(3, "Arg Error: Sleep needs Duration or Int (ms)".to_string()) }
             },
             _ => VasoType::VBit(3, format!("Time.{} not found", func))
         },
         "Sys" => match func {
             "os" => VasoType::Str(env::consts::OS.to_string()), 
             "arg" => { // <--- NUEVO: Sys.arg(index)
-                if let Some(VasoType::Int(idx)) = args.get(0) {
-                    // Offset de 2 porque 0=binary, 1=script.vs
-                    let real_idx = (idx + 2) as usize; 
-                    match env::args().nth(real_idx) {
+                if let Some(VasoType::Int(idx)) = args.first() {
+                    // Solo los argumentos que van despues del script.vs
+                    match SCRIPT_ARGS.with(|a| a.borrow().get(*idx as usize).cloned()) {
                         Some(val) => VasoType::Str(val),
                         None => VasoType::VBit(4, "No Arg".to_string())
                     }
                 } else { VasoType::VBit(3, "Sys.arg needs Int".to_string()) }
             },
-            // ... (Mantener exec igual que antes) ...
-            "exec" => {
-                if let (Some(VasoType::Str(cmd)), Some(VasoType::Str(arg1))) = (args.get(0), args.get(1)) {
-                    let is_windows = cfg!(target_os = "windows");
-                    let mut command = if is_windows {
-                        let mut c = Command::new("cmd");
-                        c.arg("/C").arg(format!("{} {}", cmd, arg1));
-                        c
-                    } else {
-                        let mut c = Command::new(cmd);
-                        c.arg(arg1);
-                        c
-                    };
-                    match command.output() {
-                        Ok(output) => {
-                            if output.status.success() { VasoType::VBit(1, "".to_string()) } 
-                            else {
-                                let err = String::from_utf8_lossy(&output.stderr).to_string();
-                                let msg = if err.is_empty() { String::from_utf8_lossy(&output.stdout).to_string() } else { err };
-                                VasoType::VBit(3, format!("CMD Failed: {}", msg.trim()))
-                            }
-                        },
-                        Err(e) => VasoType::VBit(3, format!("Exec Error: {}", e))
-                    }
-                } else { VasoType::VBit(3, "Sys.exec needs (cmd, arg)".to_string()) }
+            // Sys.exec(cmd, [args...], {cwd, env, stdin, timeout}) -> {status, code, stdout, stderr, duration}
+            // Un Str como segundo argumento es UN solo argv (no se parte por espacios).
+            "exec" => match command_args(&args) {
+                Some((cmd, argv, opts)) => process::exec(&cmd, &argv, &opts),
+                None => VasoType::VBit(3, "Sys.exec needs (cmd, [args], {options})".to_string())
+            },
+            // Sys.pipe([["cat", "f"], ["grep", "500"]], {opts})
+            "pipe" => {
+                let stages: Option<Vec<Vec<String>>> = match args.first() {
+                    Some(VasoType::List(stages)) if !stages.is_empty() => stages.iter().map(|stage| match stage {
+                        VasoType::List(words) if !words.is_empty() => Some(words.iter().map(|w| w.render()).collect()),
+                        _ => None
+                    }).collect(),
+                    _ => None
+                };
+                let opts = match args.get(1) { Some(VasoType::Map(opts)) => opts.clone(), _ => BTreeMap::new() };
+                match stages {
+                    Some(stages) => process::pipe(&stages, &opts),
+                    None => VasoType::VBit(3, "Sys.pipe needs a list of [cmd, args...] stages".to_string())
+                }
+            },
+            "sh" => match command_args(&args) {
+                Some((script, argv, opts)) => process::sh(&script, &argv, &opts),
+                None => VasoType::VBit(3, "Sys.sh needs (script, [args], {options})".to_string())
+            },
+            // Sys.quote("it's") / Sys.quote(["a b", "c"]) -> texto seguro para Sys.sh
+            "quote" => match args.first() {
+                Some(VasoType::List(items)) => VasoType::Str(items.iter().map(|a| process::shell_quote(&a.render())).collect::<Vec<_>>().join(" ")),
+                Some(val) => VasoType::Str(process::shell_quote(&val.render())),
+                None => VasoType::VBit(3, "Sys.quote needs a value".to_string())
+            },
+            // Sys.exit_codes({0: on, 1: off, 75: loading, _: error}) o un preset: "grep", "diff", "sysexits"
+            "exit_codes" => match args.first().map(ExitCodeMap::from_value) {
+                Some(Ok(map)) => { set_exit_codes(map); VasoType::VBit(1, "".to_string()) },
+                Some(Err(e)) => VasoType::VBit(3, e),
+                None => VasoType::VBit(3, "Sys.exit_codes needs a map or a preset name".to_string())
             },
             _ => VasoType::VBit(3, "Sys func error".to_string())
         },
-        // ... (Mantener Math, Json, File igual que antes) ...
-        "Math" => match func {
-            "random" => {
-                let mut rng = rand::thread_rng();
-                VasoType::Int(rng.gen_range(0..100))
-            }, 
-            _ => VasoType::VBit(3, format!("Math.{} not found", func))
-        },
+        "Math" => numbers::call(func, &args),
         "Json" => match func {
             "parse" => {
-                if let Some(VasoType::Str(json_str)) = args.get(0) {
-                    match serde_json::from_str::<Value>(json_str) {
-                        Ok(v) => VasoType::Json(v),
-                        Err(e) => VasoType::VBit(3, format!("JSON Error: {}", e))
-                    }
+                if let Some(VasoType::Str(json_str)) = args.first() {
+                    json::parse(json_str)
                 } else { VasoType::VBit(3, format!("Json.parse needs (Str), got {:?}", args)) }
             },
-            "get" => {
-                if let (Some(VasoType::Json(val)), Some(VasoType::Str(key))) = (args.get(0), args.get(1)) {
-                    match val.get(key) {
-                        Some(v) => {
-                            if let Some(i) = v.as_i64() { VasoType::Int(i as i32) }
-                            else if let Some(s) = v.as_str() { VasoType::Str(s.to_string()) }
-                            else if let Some(b) = v.as_bool() { 
-                                if b { VasoType::VBit(1, "".to_string()) } else { VasoType::VBit(0, "".to_string()) }
-                            }
-                            else { VasoType::VBit(4, "Complex Type".to_string()) }
-                        },
-                        None => VasoType::VBit(4, format!("Field '{}' not found", key))
-                    }
-                } else { 
-                    VasoType::VBit(3, format!("Json.get needs (Json, Str), got {:?}", args))
-                }
+            // Json.get(doc, "spec.containers[0].image") -> valor nativo (Map, List, Int, Float...)
+            "get" => match (args.first(), args.get(1)) {
+                (Some(doc), Some(VasoType::Str(path))) => match json::from_native(doc) {
+                    Ok(doc) => json::get(&doc, path),
+                    Err(e) => VasoType::VBit(3, e)
+                },
+                _ => VasoType::VBit(3, format!("Json.get needs (Json, Str), got {:?}", args))
+            },
+            // Json.set(doc, "metadata.labels.env", "prod") -> documento nuevo
+            "set" => match (args.first(), args.get(1), args.get(2)) {
+                (Some(doc), Some(VasoType::Str(path)), Some(value)) => {
+                    let updated = json::from_native(doc).and_then(|mut doc| {
+                        json::set(&mut doc, path, json::from_native(value)?)?;
+                        Ok(doc)
+                    });
+                    updated.map(VasoType::Json).unwrap_or_else(|e| VasoType::VBit(3, e))
+                },
+                _ => VasoType::VBit(3, format!("Json.set needs (Json, Str, value), got {:?}", args))
+            },
+            // Json.stringify(doc) compacto, Json.stringify(doc, on) con sangria
+            "stringify" => match args.first() {
+                Some(v) => json::stringify(v, matches!(args.get(1), Some(VasoType::VBit(1, _)))),
+                None => VasoType::VBit(3, "Json.stringify needs a value".to_string())
+            },
+            // Json.validate(doc, schema) -> on / error("2 schema violations: /spec/replicas: ... | /name: ...")
+            "validate" => match (args.first(), args.get(1)) {
+                (Some(doc), Some(schema)) => match (json::from_native(doc), json::from_native(schema)) {
+                    (Ok(doc), Ok(schema)) => schema::validate(&doc, &schema),
+                    (Err(e), _) | (_, Err(e)) => VasoType::VBit(3, e)
+                },
+                _ => VasoType::VBit(3, format!("Json.validate needs (Json, schema), got {:?}", args))
+            },
+            "native" => match args.first().map(json::from_native) {
+                Some(Ok(v)) => json::to_native(&v),
+                Some(Err(e)) => VasoType::VBit(3, e),
+                None => VasoType::VBit(3, "Json.native needs a value".to_string())
+            },
+            "from" => match args.first().map(json::from_native) {
+                Some(Ok(v)) => VasoType::Json(v),
+                Some(Err(e)) => VasoType::VBit(3, e),
+                None => VasoType::VBit(3, "Json.from needs a value".to_string())
             },
             _ => VasoType::VBit(3, format!("Json.{} not found", func))
         },
+        // Mismo valor que Json: Json.get(Yaml.parse(text), "spec.replicas")
+        "Yaml" => match (func, args.first()) {
+            ("parse", Some(VasoType::Str(text))) => formats::yaml_parse(text),
+            ("parse_all", Some(VasoType::Str(text))) => match formats::yaml_parse_all(text) {
+                Ok(docs) => VasoType::List(docs.into_iter().map(VasoType::Json).collect()),
+                Err(e) => VasoType::VBit(3, e)
+            },
+            ("stringify", Some(doc)) => match json::from_native(doc) {
+                Ok(doc) => formats::yaml_stringify(&[doc]),
+                Err(e) => VasoType::VBit(3, e)
+            },
+            // Varios documentos separados por "---"
+            ("stringify_all", Some(VasoType::List(docs))) => match docs.iter().map(json::from_native).collect::<Result<Vec<_>, _>>() {
+                Ok(docs) => formats::yaml_stringify(&docs),
+                Err(e) => VasoType::VBit(3, e)
+            },
+            ("parse" | "parse_all" | "stringify" | "stringify_all", _) => VasoType::VBit(3, format!("Yaml.{} got invalid args {:?}", func, args)),
+            _ => VasoType::VBit(3, format!("Yaml.{} not found", func))
+        },
+        // Csv.read("sales.csv", {delimiter: ";"}) -> [{region: "Norte", revenue: 1200}, error("Csv line 4: ..."), ...]
+        "Csv" => match (func, args.first(), args.get(1)) {
+            ("read" | "parse", Some(VasoType::Str(src)), opts) => match CsvOptions::from_value(opts) {
+                Ok(opts) if func == "read" => tables::read(src, &opts),
+                Ok(opts) => tables::parse(src, &opts),
+                Err(e) => VasoType::VBit(3, e)
+            },
+            ("stringify", Some(data), opts) => match CsvOptions::from_value(opts).and_then(|mut o| tables::stringify(tables::rows_of(data, &mut o)?, &o)) {
+                Ok((text, _)) => VasoType::Str(text),
+                Err(e) => VasoType::VBit(3, e)
+            },
+            ("write", Some(VasoType::Str(path)), Some(data)) => match CsvOptions::from_value(args.get(2)).and_then(|mut o| Ok((tables::rows_of(data, &mut o)?, o))) {
+                Ok((rows, opts)) => tables::write(path, rows, &opts),
+                Err(e) => VasoType::VBit(3, e)
+            },
+            ("read" | "parse" | "stringify" | "write", _, _) => VasoType::VBit(3, format!("Csv.{} got invalid args {:?}", func, args)),
+            _ => VasoType::VBit(3, format!("Csv.{} not found", func))
+        },
+        "Str" => strings::call(func, &args),
+        "Regex" => patterns::call(func, &args),
+        "List" => lists::call(func, &args),
+        "VBit" => vbits::call(func, &args),
+        "Toml" => match (func, args.first()) {
+            ("parse", Some(VasoType::Str(text))) => formats::toml_parse(text),
+            ("stringify", Some(doc)) => match json::from_native(doc) {
+                Ok(doc) => formats::toml_stringify(&doc),
+                Err(e) => VasoType::VBit(3, e)
+            },
+            ("parse" | "stringify", _) => VasoType::VBit(3, format!("Toml.{} got invalid args {:?}", func, args)),
+            _ => VasoType::VBit(3, format!("Toml.{} not found", func))
+        },
         "File" => match func {
             "read" => {
-                if let Some(VasoType::Str(path)) = args.get(0) {
+                if let Some(VasoType::Str(path)) = args.first() {
                     match fs::read_to_string(path) {
                         Ok(content) => VasoType::Str(content),
                         Err(e) => VasoType::VBit(3, format!("IO Error: {}", e))
@@ -112,15 +264,170 @@ pub fn call_std_function(module: &str, func: &str, args: Vec<VasoType>) -> VasoT
                 } else { VasoType::VBit(3, "Arg Error".to_string()) }
             },
             "write" => {
-                 if let (Some(VasoType::Str(path)), Some(VasoType::Str(content))) = (args.get(0), args.get(1)) {
+                 if let (Some(VasoType::Str(path)), Some(VasoType::Str(content))) = (args.first(), args.get(1)) {
                     match fs::write(path, content) {
                         Ok(_) => VasoType::VBit(1, "File Written".to_string()),
                         Err(e) => VasoType::VBit(3, format!("Write Error: {}", e))
                     }
                  } else { VasoType::VBit(3, "Arg Error".to_string()) }
             },
+            "exists" | "delete" | "mkdir" | "list" | "glob" | "stat" | "read_lines" => match args.first() {
+                Some(VasoType::Str(path)) => match func {
+                    "exists" => files::exists(path),
+                    // File.delete(dir, {recursive: on}) borra tambien el contenido
+                    "delete" => files::delete(path, matches!(args.get(1), Some(VasoType::Map(o)) if matches!(o.get("recursive"), Some(VasoType::VBit(1, _))))),
+                    "mkdir" => files::mkdir(path),
+                    "list" => files::list(path),
+                    "glob" => files::glob(path),
+                    "stat" => files::stat(path),
+                    _ => files::read_lines(path),
+                },
+                _ => VasoType::VBit(3, format!("File.{} needs a path", func))
+            },
+            "append" | "copy" | "move" | "write_atomic" => match (args.first(), args.get(1)) {
+                (Some(VasoType::Str(a)), Some(VasoType::Str(b))) => match func {
+                    "append" => files::append(a, b),
+                    "write_atomic" => files::write_atomic(a, b),
+                    "copy" => files::copy(a, b),
+                    _ => files::rename(a, b),
+                },
+                _ => VasoType::VBit(3, format!("File.{} needs (Str, Str)", func))
+            },
             _ => VasoType::VBit(3, format!("File.{} not found", func))
         },
+        // --- Locks: Lock.acquire("deploy.lock", 30s) ... Lock.release("deploy.lock") ---
+        "Lock" => match (func, args.first(), args.get(1)) {
+            ("acquire", Some(VasoType::Str(path)), None) => locks::acquire(path, None),
+            ("acquire", Some(VasoType::Str(path)), Some(t)) => match as_millis(t) {
+                Some(ms) => locks::acquire(path, Some(ms)),
+                None => VasoType::VBit(3, "Lock.acquire timeout must be a Duration".to_string())
+            },
+            ("release", Some(VasoType::Str(path)), None) => locks::release(path),
+            ("acquire" | "release", _, _) => VasoType::VBit(3, format!("Lock.{} needs a lock file path", func)),
+            _ => VasoType::VBit(3, format!("Lock.{} not found", func))
+        },
+        // --- Rutas: Path.join(Path.script_dir(), "config.json") ---
+        "Path" => {
+            // Path.join("a", "b") o Path.join(["a", "b"])
+            let args = match args.as_slice() {
+                [VasoType::List(items)] if func == "join" => items.clone(),
+                _ => args
+            };
+            let strs: Option<Vec<String>> = args.iter().map(|a| match a {
+                VasoType::Str(s) => Some(s.clone()),
+                _ => None
+            }).collect();
+            let strs = match strs {
+                Some(strs) => strs,
+                None => return VasoType::VBit(3, format!("Path.{} needs Str arguments", func))
+            };
+            match (func, strs.as_slice()) {
+                ("script_dir", []) => paths::script_dir(),
+                ("join", parts) if !parts.is_empty() => paths::join(parts),
+                ("parent", [p]) => paths::parent(p),
+                ("file_name", [p]) => paths::file_name(p),
+                ("stem", [p]) => paths::stem(p),
+                ("extension", [p]) => paths::extension(p),
+                ("with_extension", [p, ext]) => paths::with_extension(p, ext),
+                ("absolute", [p]) => paths::absolute(p),
+                ("normalize", [p]) => paths::normalize(p),
+                ("relative_to", [p, base]) => paths::relative_to(p, base),
+                ("script_dir" | "join" | "parent" | "file_name" | "stem" | "extension" | "with_extension" | "absolute" | "normalize" | "relative_to", _) =>
+                    VasoType::VBit(3, format!("Path.{}: wrong number of arguments", func)),
+                _ => VasoType::VBit(3, format!("Path.{} not found", func))
+            }
+        },
+        // --- Entorno: Env.get("CI") es unknown si no existe; Env.require lo convierte en error ---
+        "Env" => {
+            let name = match (func, args.first()) {
+                ("all", _) => String::new(),
+                (_, Some(VasoType::Str(name))) => name.clone(),
+                _ => return VasoType::VBit(3, format!("Env.{} needs a name", func))
+            };
+            match func {
+                "get" => match (environ::get(&name), args.get(1)) {
+                    (Some(val), _) => VasoType::Str(val),
+                    (None, Some(default)) => default.clone(),
+                    (None, None) => VasoType::VBit(4, format!("{} not set", name))
+                },
+                "require" => match environ::get(&name) {
+                    Some(val) => VasoType::Str(val),
+                    None => VasoType::VBit(3, format!("Missing environment variable {}", name))
+                },
+                "set" => match args.get(1) {
+                    Some(val) => { environ::set(&name, Some(val.render())); VasoType::VBit(1, "".to_string()) },
+                    None => VasoType::VBit(3, "Env.set needs (name, value)".to_string())
+                },
+                "unset" => { environ::set(&name, None); VasoType::VBit(1, "".to_string()) },
+                "all" => VasoType::Map(environ::all().into_iter().map(|(k, v)| (k, VasoType::Str(v))).collect()),
+                // Env.load(".env") / Env.load(".env", {override: on})
+                "load" => {
+                    let force = matches!(args.get(1), Some(VasoType::Map(o)) if matches!(o.get("override"), Some(VasoType::VBit(1, _))));
+                    environ::load(&name, force)
+                },
+                _ => VasoType::VBit(3, format!("Env.{} not found", func))
+            }
+        },
+        // --- Procesos en segundo plano: h = Proc.spawn(...); h.status(); h.wait(5s); h.kill() ---
+        "Proc" => {
+            if func == "spawn" {
+                return match command_args(&args) {
+                    Some((cmd, argv, opts)) => process::spawn(&cmd, &argv, &opts),
+                    None => VasoType::VBit(3, "Proc.spawn needs (cmd, [args], {options})".to_string())
+                };
+            }
+            let id = match args.first() {
+                Some(VasoType::Proc(id)) => *id,
+                _ => return VasoType::VBit(3, format!("Proc.{} needs a process handle", func))
+            };
+            match func {
+                "pid" => process::proc_pid(id),
+                "status" => process::proc_status(id),
+                "wait" => match args.get(1) {
+                    None => process::proc_wait(id, None),
+                    Some(t) => match as_millis(t) {
+                        Some(ms) => process::proc_wait(id, Some(ms)),
+                        None => VasoType::VBit(3, "Proc.wait timeout must be a Duration".to_string())
+                    }
+                },
+                "kill" => process::proc_kill(id, args.get(1).unwrap_or(&VasoType::Str("TERM".to_string()))),
+                "stdout" => process::proc_output(id, false),
+                "stderr" => process::proc_output(id, true),
+                _ => VasoType::VBit(3, format!("Proc.{} not found", func))
+            }
+        },
         _ => VasoType::VBit(3, format!("Module {} not found", module))
     }
+}
+
+// valor.metodo(args) -> Modulo.metodo(valor, args): h.wait(5s) == Proc.wait(h, 5s)
+pub fn call_method(target: &VasoType, method: &str, mut args: Vec<VasoType>) -> VasoType {
+    let module = match target {
+        VasoType::Proc(_) => "Proc",
+        VasoType::Str(_) => "Str",
+        VasoType::List(_) => "List",
+        VasoType::VBit(3, _) => return target.clone(),
+        other => return VasoType::VBit(3, format!("No method '{}' on {}", method, other))
+    };
+    args.insert(0, target.clone());
+    call_std_function(module, method, args)
+}
+
+// (cmd, [args], {options}) comun a Sys.exec y Proc.spawn.
+// Un Str como segundo argumento es UN solo argv (no se parte por espacios).
+fn command_args(args: &[VasoType]) -> Option<(String, Vec<String>, BTreeMap<String, VasoType>)> {
+    let cmd = match args.first() {
+        Some(VasoType::Str(cmd)) => cmd.clone(),
+        _ => return None
+    };
+    let argv: Vec<String> = match args.get(1) {
+        None | Some(VasoType::Map(_)) => Vec::new(),
+        Some(VasoType::List(items)) => items.iter().map(|a| a.render()).collect(),
+        Some(other) => vec![other.render()],
+    };
+    let opts = match args.iter().skip(1).find(|a| matches!(a, VasoType::Map(_))) {
+        Some(VasoType::Map(opts)) => opts.clone(),
+        _ => BTreeMap::new(),
+    };
+    Some((cmd, argv, opts))
 }
\ No newline at end of file
@@ -0,0 +1,218 @@
+use crate::types::VasoType;
+use crate::logic::bool_vbit;
+
+// --- TEXTO: Str.split / trim / replace / format ... (y metodos: line.trim()) ---
+// Los indices y longitudes cuentan caracteres, no bytes: "ñandú".len() == 5.
+
+fn err(msg: String) -> VasoType {
+    VasoType::VBit(3, msg)
+}
+
+fn list(items: impl Iterator<Item = String>) -> VasoType {
+    VasoType::List(items.map(VasoType::Str).collect())
+}
+
+// Indice de caracter; negativo cuenta desde el final y se recorta a [0, len]
+fn char_index(i: i32, len: usize) -> usize {
+    let idx = if i < 0 { len as i64 + i as i64 } else { i as i64 };
+    idx.clamp(0, len as i64) as usize
+}
+
+fn pad(s: &str, width: i32, fill: &str, left: bool) -> VasoType {
+    let mut fill_chars = fill.chars();
+    let (Some(f), None) = (fill_chars.next(), fill_chars.next()) else {
+        return err(format!("Str.pad fill must be one character, got '{}'", fill));
+    };
+    let missing = (width.max(0) as usize).saturating_sub(s.chars().count());
+    let padding: String = std::iter::repeat_n(f, missing).collect();
+    VasoType::Str(if left { padding + s } else { format!("{}{}", s, padding) })
+}
+
+// {:>8} {:<10} {:^6} {:08} {:.2} {:8.3}; {{ y }} son llaves literales
+struct Spec {
+    align: Option<char>,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+}
+
+fn parse_spec(spec: &str) -> Result<Spec, String> {
+    let bad = || format!("Str.format: invalid spec '{{:{}}}'", spec);
+    let mut rest = spec;
+    let mut align = None;
+    if let Some(c) = rest.chars().next().filter(|c| matches!(c, '<' | '>' | '^')) {
+        align = Some(c);
+        rest = &rest[1..];
+    }
+    let zero = rest.starts_with('0') && rest.len() > 1 && !rest.starts_with("0.");
+    if zero { rest = &rest[1..]; }
+    let (width, precision) = match rest.split_once('.') {
+        Some((w, p)) => (w, Some(p.parse::<usize>().map_err(|_| bad())?)),
+        None => (rest, None),
+    };
+    let width = if width.is_empty() { 0 } else { width.parse::<usize>().map_err(|_| bad())? };
+    Ok(Spec { align, zero, width, precision })
+}
+
+fn format_value(val: &VasoType, spec: &Spec) -> Result<String, String> {
+    let numeric = matches!(val, VasoType::Int(_) | VasoType::Float(_));
+    let text = match (val, spec.precision) {
+        (VasoType::Float(x), Some(p)) => format!("{:.*}", p, x),
+        (VasoType::Int(n), Some(p)) => format!("{:.*}", p, *n as f64),
+        // Con precision un texto se corta: {:.3} de "abcdef" -> "abc"
+        (other, Some(p)) if !numeric => other.render().chars().take(p).collect(),
+        (other, _) => other.render(),
+    };
+    let len = text.chars().count();
+    if len >= spec.width { return Ok(text); }
+    let missing = spec.width - len;
+    if spec.zero && numeric {
+        let (sign, digits) = if let Some(d) = text.strip_prefix('-') { ("-", d) } else { ("", text.as_str()) };
+        return Ok(format!("{}{}{}", sign, "0".repeat(missing), digits));
+    }
+    // Como en Rust: numeros a la derecha, texto a la izquierda
+    let align = spec.align.unwrap_or(if numeric { '>' } else { '<' });
+    Ok(match align {
+        '>' => format!("{}{}", " ".repeat(missing), text),
+        '^' => format!("{}{}{}", " ".repeat(missing / 2), text, " ".repeat(missing - missing / 2)),
+        _ => format!("{}{}", text, " ".repeat(missing)),
+    })
+}
+
+// Str.format("{:<10} {:>6.2}", name, ratio)
+pub fn format(template: &str, values: &[VasoType]) -> VasoType {
+    let mut out = String::new();
+    let mut chars = template.chars().peekable();
+    let mut next = 0;
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => { chars.next(); out.push('{'); },
+            '}' if chars.peek() == Some(&'}') => { chars.next(); out.push('}'); },
+            '{' => {
+                let mut inner = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(ch) => inner.push(ch),
+                        None => return err("Str.format: unclosed '{'".to_string()),
+                    }
+                }
+                let spec = match inner.strip_prefix(':') {
+                    Some(s) => s,
+                    None if inner.is_empty() => "",
+                    None => return err(format!("Str.format: invalid placeholder '{{{}}}'", inner)),
+                };
+                let Some(val) = values.get(next) else {
+                    return err(format!("Str.format: {} placeholders but {} values", next + 1, values.len()));
+                };
+                next += 1;
+                match parse_spec(spec).and_then(|s| format_value(val, &s)) {
+                    Ok(text) => out.push_str(&text),
+                    Err(e) => return err(e),
+                }
+            },
+            '}' => return err("Str.format: unmatched '}'".to_string()),
+            c => out.push(c),
+        }
+    }
+    if next < values.len() {
+        return err(format!("Str.format: {} placeholders but {} values", next, values.len()));
+    }
+    VasoType::Str(out)
+}
+
+pub fn call(func: &str, args: &[VasoType]) -> VasoType {
+    use VasoType::{Int, List, Str};
+    match (func, args) {
+        ("len", [Str(s)]) => Int(s.chars().count() as i32),
+        ("split", [Str(s)]) => list(s.split_whitespace().map(String::from)),
+        ("split", [Str(s), Str(sep)]) if !sep.is_empty() => list(s.split(sep.as_str()).map(String::from)),
+        ("join", [List(items), Str(sep)]) => Str(items.iter().map(VasoType::render).collect::<Vec<_>>().join(sep)),
+        ("join", [List(items)]) => Str(items.iter().map(VasoType::render).collect()),
+        ("trim", [Str(s)]) => Str(s.trim().to_string()),
+        ("trim_start", [Str(s)]) => Str(s.trim_start().to_string()),
+        ("trim_end", [Str(s)]) => Str(s.trim_end().to_string()),
+        ("lines", [Str(s)]) => list(s.lines().map(String::from)),
+        ("contains", [Str(s), Str(needle)]) => bool_vbit(s.contains(needle.as_str())),
+        ("starts_with", [Str(s), Str(prefix)]) => bool_vbit(s.starts_with(prefix.as_str())),
+        ("ends_with", [Str(s), Str(suffix)]) => bool_vbit(s.ends_with(suffix.as_str())),
+        ("replace", [Str(s), Str(from), Str(to)]) if !from.is_empty() => Str(s.replace(from.as_str(), to)),
+        ("upper", [Str(s)]) => Str(s.to_uppercase()),
+        ("lower", [Str(s)]) => Str(s.to_lowercase()),
+        // pad rellena por la derecha (texto alineado a la izquierda), pad_left por la izquierda
+        ("pad", [Str(s), Int(w)]) => pad(s, *w, " ", false),
+        ("pad", [Str(s), Int(w), Str(fill)]) => pad(s, *w, fill, false),
+        ("pad_left", [Str(s), Int(w)]) => pad(s, *w, " ", true),
+        ("pad_left", [Str(s), Int(w), Str(fill)]) => pad(s, *w, fill, true),
+        ("repeat", [Str(s), Int(n)]) if *n >= 0 => Str(s.repeat(*n as usize)),
+        ("substring", [Str(s), Int(start)]) => {
+            let len = s.chars().count();
+            Str(s.chars().skip(char_index(*start, len)).collect())
+        },
+        ("substring", [Str(s), Int(start), Int(end)]) => {
+            let len = s.chars().count();
+            let (from, to) = (char_index(*start, len), char_index(*end, len));
+            Str(s.chars().skip(from).take(to.saturating_sub(from)).collect())
+        },
+        ("to_int", [Str(s)]) => s.trim().parse::<i32>().map(Int)
+            .unwrap_or_else(|_| err(format!("Str.to_int: '{}' is not an integer", s))),
+        ("to_float", [Str(s)]) => match s.trim().parse::<f64>() {
+            Ok(x) if x.is_finite() => VasoType::Float(x),
+            _ => err(format!("Str.to_float: '{}' is not a number", s)),
+        },
+        ("format", [Str(template), values @ ..]) => format(template, values),
+        (
+            "len" | "split" | "join" | "trim" | "trim_start" | "trim_end" | "lines" | "contains" | "starts_with"
+            | "ends_with" | "replace" | "upper" | "lower" | "pad" | "pad_left" | "repeat" | "substring"
+            | "to_int" | "to_float" | "format",
+            _,
+        ) => err(format!("Str.{} got invalid args {:?}", func, args)),
+        _ => err(format!("Str.{} not found", func)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(text: &str) -> VasoType {
+        VasoType::Str(text.to_string())
+    }
+
+    #[test]
+    fn parses_specs() {
+        let spec = parse_spec(">08.2").unwrap();
+        assert_eq!((spec.align, spec.zero, spec.width, spec.precision), (Some('>'), true, 8, Some(2)));
+        let spec = parse_spec("0.3").unwrap();
+        assert_eq!((spec.align, spec.zero, spec.width, spec.precision), (None, false, 0, Some(3)));
+        let spec = parse_spec("").unwrap();
+        assert_eq!((spec.align, spec.zero, spec.width, spec.precision), (None, false, 0, None));
+        assert!(parse_spec("x5").is_err());
+        assert!(parse_spec("5.").is_err());
+    }
+
+    #[test]
+    fn aligns_and_pads() {
+        assert_eq!(format("[{:<6}|{:>6}|{:^6}]", &[s("ab"), s("ab"), s("ab")]), s("[ab    |    ab|  ab  ]"));
+        // Sin alineacion: numeros a la derecha, texto a la izquierda
+        assert_eq!(format("[{:5}|{:5}]", &[VasoType::Int(42), s("ok")]), s("[   42|ok   ]"));
+        assert_eq!(format("{:06}", &[VasoType::Int(-42)]), s("-00042"));
+        assert_eq!(format("{:8.3}", &[VasoType::Float(1.23456)]), s("   1.235"));
+        assert_eq!(format("{:.2} {:.3}", &[VasoType::Int(2), s("abcdef")]), s("2.00 abc"));
+        assert_eq!(format("{{{}}}", &[s("x")]), s("{x}"));
+    }
+
+    #[test]
+    fn reports_template_errors() {
+        let msg = |v: VasoType| match v {
+            VasoType::VBit(3, m) => m,
+            other => panic!("expected an error, got {:?}", other),
+        };
+        assert_eq!(msg(format("{} {}", &[VasoType::Int(1)])), "Str.format: 2 placeholders but 1 values");
+        assert_eq!(msg(format("{}", &[VasoType::Int(1), VasoType::Int(2)])), "Str.format: 1 placeholders but 2 values");
+        assert_eq!(msg(format("{:q}", &[VasoType::Int(1)])), "Str.format: invalid spec '{:q}'");
+        assert_eq!(msg(format("{name}", &[VasoType::Int(1)])), "Str.format: invalid placeholder '{name}'");
+        assert_eq!(msg(format("{", &[])), "Str.format: unclosed '{'");
+        assert_eq!(msg(format("}", &[])), "Str.format: unmatched '}'");
+    }
+}
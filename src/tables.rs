@@ -0,0 +1,247 @@
+use crate::types::VasoType;
+use std::fs;
+
+// --- CSV: Csv.read / Csv.write ---
+// Una fila mal formada no aborta la lectura: queda en su sitio como un Error,
+// y la dominancia de estados la aisla al agregar (igual que 08_resilient_analytics).
+
+pub struct CsvOptions {
+    header: bool,
+    delimiter: u8,
+    // "42" -> Int, "0.5" -> Float, "" -> unknown
+    infer: bool,
+    columns: Option<Vec<String>>,
+    // {columns: [...], rows: [...]}: la cabecera viaja con las filas y fija el orden al escribir
+    table: bool,
+}
+
+impl CsvOptions {
+    // {header: on, delimiter: ";", infer: off, columns: ["id", "total"], table: on}
+    pub fn from_value(opts: Option<&VasoType>) -> Result<Self, String> {
+        let mut options = CsvOptions { header: true, delimiter: b',', infer: true, columns: None, table: false };
+        let fields = match opts {
+            None => return Ok(options),
+            Some(VasoType::Map(fields)) => fields,
+            Some(other) => return Err(format!("Csv options must be a map, got {}", other.render())),
+        };
+        for (key, val) in fields {
+            match (key.as_str(), val) {
+                ("header", VasoType::VBit(lvl @ (0 | 1), _)) => options.header = *lvl == 1,
+                ("infer", VasoType::VBit(lvl @ (0 | 1), _)) => options.infer = *lvl == 1,
+                ("table", VasoType::VBit(lvl @ (0 | 1), _)) => options.table = *lvl == 1,
+                ("delimiter", VasoType::Str(d)) if d.len() == 1 => options.delimiter = d.as_bytes()[0],
+                ("delimiter", VasoType::Str(d)) if d == "\\t" || d == "tab" => options.delimiter = b'\t',
+                ("columns", VasoType::List(cols)) => options.columns = Some(column_names(cols)?),
+                (key, val) => return Err(format!("Invalid Csv option {}: {}", key, val.render())),
+            }
+        }
+        Ok(options)
+    }
+}
+
+fn column_names(cols: &[VasoType]) -> Result<Vec<String>, String> {
+    cols.iter().map(|c| match c {
+        VasoType::Str(s) => Ok(s.clone()),
+        other => Err(format!("Csv columns must be strings, got {}", other.render())),
+    }).collect()
+}
+
+fn looks_numeric(s: &str) -> bool {
+    s.chars().any(|c| c.is_ascii_digit()) && s.chars().all(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+}
+
+// "007" o un codigo postal "01234" son texto: como numero perderian el cero
+fn leading_zero(s: &str) -> bool {
+    let digits = s.trim_start_matches(['-', '+']).as_bytes();
+    digits.len() > 1 && digits[0] == b'0' && digits[1].is_ascii_digit()
+}
+
+fn cell(raw: &str, infer: bool) -> VasoType {
+    if !infer { return VasoType::Str(raw.to_string()); }
+    if raw.is_empty() { return VasoType::VBit(4, "".to_string()); }
+    if looks_numeric(raw) && !leading_zero(raw) {
+        if let Ok(n) = raw.parse::<i32>() { return VasoType::Int(n); }
+        if let Ok(x) = raw.parse::<f64>() { return VasoType::Float(x); }
+    }
+    VasoType::Str(raw.to_string())
+}
+
+// Con cabecera: lista de mapas {columna: valor}; sin cabecera: lista de listas.
+// Con {table: on} las filas van dentro de {columns, rows}.
+pub fn parse(text: &str, opts: &CsvOptions) -> VasoType {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .delimiter(opts.delimiter)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let mut records = reader.records();
+
+    let header: Option<Vec<String>> = match (&opts.columns, opts.header) {
+        (Some(cols), false) => Some(cols.clone()),
+        (_, true) => match records.next() {
+            Some(Ok(first)) => Some(opts.columns.clone().unwrap_or_else(|| first.iter().map(|h| h.trim().to_string()).collect())),
+            Some(Err(e)) => return VasoType::VBit(3, format!("Csv header: {}", e)),
+            None => return table(Vec::new(), Vec::new(), opts),
+        },
+        (None, false) => None,
+    };
+
+    let mut rows = Vec::new();
+    let mut width = header.as_ref().map(Vec::len);
+    for result in records {
+        let record = match result {
+            Ok(r) => r,
+            Err(e) => {
+                let line = e.position().map(|p| p.line()).unwrap_or(0);
+                rows.push(VasoType::VBit(3, format!("Csv line {}: {}", line, e)));
+                continue;
+            }
+        };
+        let line = record.position().map(|p| p.line()).unwrap_or(0);
+        let expected = *width.get_or_insert(record.len());
+        if record.len() != expected {
+            rows.push(VasoType::VBit(3, format!("Csv line {}: expected {} fields, got {}", line, expected, record.len())));
+            continue;
+        }
+        rows.push(match &header {
+            Some(names) => VasoType::Map(names.iter().cloned().zip(record.iter().map(|v| cell(v, opts.infer))).collect()),
+            None => VasoType::List(record.iter().map(|v| cell(v, opts.infer)).collect()),
+        });
+    }
+    table(header.unwrap_or_default(), rows, opts)
+}
+
+fn table(columns: Vec<String>, rows: Vec<VasoType>, opts: &CsvOptions) -> VasoType {
+    if !opts.table { return VasoType::List(rows); }
+    VasoType::Map([
+        ("columns".to_string(), VasoType::List(columns.into_iter().map(VasoType::Str).collect())),
+        ("rows".to_string(), VasoType::List(rows)),
+    ].into_iter().collect())
+}
+
+// Lo que aceptan Csv.write/stringify: una lista de filas o el {columns, rows} de
+// {table: on}, cuyas columnas dan el orden salvo que las opciones traigan `columns`.
+pub fn rows_of<'a>(data: &'a VasoType, opts: &mut CsvOptions) -> Result<&'a [VasoType], String> {
+    let fields = match data {
+        VasoType::List(rows) => return Ok(rows),
+        VasoType::Map(fields) => fields,
+        other => return Err(format!("Csv rows must be a list or a {{columns, rows}} table, got {}", other.render())),
+    };
+    let rows = match fields.get("rows") {
+        Some(VasoType::List(rows)) => rows,
+        _ => return Err("Csv table needs a 'rows' list".to_string()),
+    };
+    match (fields.get("columns"), &opts.columns) {
+        (Some(VasoType::List(cols)), None) => opts.columns = Some(column_names(cols)?),
+        (None | Some(VasoType::List(_)), _) => {},
+        (Some(other), _) => return Err(format!("Csv table columns must be a list, got {}", other.render())),
+    }
+    Ok(rows)
+}
+
+pub fn read(path: &str, opts: &CsvOptions) -> VasoType {
+    match fs::read_to_string(path) {
+        Ok(text) => parse(&text, opts),
+        Err(e) => VasoType::VBit(3, format!("Csv.read: '{}': {}", path, e)),
+    }
+}
+
+fn text(v: &VasoType) -> String {
+    match v {
+        VasoType::VBit(4, msg) if msg.is_empty() => String::new(),
+        other => other.render(),
+    }
+}
+
+// Filas de error se saltan (se cuentan en el mensaje del `on`); el orden de columnas
+// sale de `columns` (o de la tabla, ver rows_of) o, si no, de las claves de los mapas.
+pub fn stringify(rows: &[VasoType], opts: &CsvOptions) -> Result<(String, usize), String> {
+    let columns = opts.columns.clone().unwrap_or_else(|| {
+        let mut names: Vec<String> = Vec::new();
+        for row in rows {
+            if let VasoType::Map(fields) = row {
+                for key in fields.keys() {
+                    if !names.contains(key) { names.push(key.clone()); }
+                }
+            }
+        }
+        names
+    });
+
+    let mut writer = csv::WriterBuilder::new().delimiter(opts.delimiter).flexible(true).from_writer(Vec::new());
+    let csv_error = |e: csv::Error| format!("Csv Error: {}", e);
+    if opts.header && !columns.is_empty() {
+        writer.write_record(&columns).map_err(csv_error)?;
+    }
+    let mut skipped = 0;
+    for row in rows {
+        let record: Vec<String> = match row {
+            VasoType::Map(fields) => columns.iter().map(|c| fields.get(c).map(text).unwrap_or_default()).collect(),
+            VasoType::List(cells) => cells.iter().map(text).collect(),
+            VasoType::VBit(3, _) => { skipped += 1; continue; },
+            other => return Err(format!("Csv rows must be maps or lists, got {}", other.render())),
+        };
+        writer.write_record(&record).map_err(csv_error)?;
+    }
+    let bytes = writer.into_inner().map_err(|e| format!("Csv Error: {}", e))?;
+    Ok((String::from_utf8_lossy(&bytes).to_string(), skipped))
+}
+
+pub fn write(path: &str, rows: &[VasoType], opts: &CsvOptions) -> VasoType {
+    let (content, skipped) = match stringify(rows, opts) {
+        Ok(out) => out,
+        Err(e) => return VasoType::VBit(3, e),
+    };
+    if let Err(e) = fs::write(path, content) {
+        return VasoType::VBit(3, format!("Csv.write: '{}': {}", path, e));
+    }
+    let note = if skipped > 0 { format!("{} error rows skipped", skipped) } else { String::new() };
+    VasoType::VBit(1, note)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opts(table: bool) -> CsvOptions {
+        let table = VasoType::Map([("table".to_string(), VasoType::VBit(table as u8, String::new()))].into_iter().collect());
+        CsvOptions::from_value(Some(&table)).unwrap()
+    }
+
+    fn rows(v: VasoType) -> Vec<VasoType> {
+        match v {
+            VasoType::List(rows) => rows,
+            other => panic!("expected rows, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn broken_rows_stay_in_place_as_errors() {
+        let parsed = rows(parse("id,amount\n1,10\n2\n3,30\n", &opts(false)));
+        assert_eq!(parsed.len(), 3);
+        assert_eq!(parsed[1], VasoType::VBit(3, "Csv line 3: expected 2 fields, got 1".to_string()));
+        let (text, skipped) = stringify(&parsed, &opts(false)).unwrap();
+        assert_eq!((text.as_str(), skipped), ("amount,id\n10,1\n30,3\n", 1));
+    }
+
+    #[test]
+    fn table_round_trip_keeps_column_order() {
+        let table = parse("zone,id,amount\nnorth,1,10\n", &opts(true));
+        let mut o = opts(false);
+        let parsed = rows_of(&table, &mut o).unwrap();
+        assert_eq!(stringify(parsed, &o).unwrap().0, "zone,id,amount\nnorth,1,10\n");
+    }
+
+    #[test]
+    fn leading_zeros_stay_text() {
+        let parsed = rows(parse("code,n,x\n007,7,0.5\n", &opts(false)));
+        match &parsed[0] {
+            VasoType::Map(fields) => {
+                assert_eq!(fields["code"], VasoType::Str("007".to_string()));
+                assert_eq!(fields["n"], VasoType::Int(7));
+                assert_eq!(fields["x"], VasoType::Float(0.5));
+            },
+            other => panic!("expected a map, got {:?}", other),
+        }
+    }
+}
@@ -1,4 +1,157 @@
-use logos::Logos;
+use logos::{Lexer, Logos};
+use std::ops::Range;
+
+// Trozo de un string interpolado: texto fijo o los tokens de un ${...}
+#[derive(Debug, PartialEq, Clone)]
+pub enum StrPart {
+    Text(String),
+    Expr(Vec<(Token, Range<usize>)>),
+}
+
+// Escape que empieza justo despues de '\\'. Devuelve el caracter y los bytes consumidos.
+// Escapes desconocidos (\d, \w...) se dejan tal cual para no romper regexes.
+fn unescape_at(rest: &str) -> Option<(String, usize)> {
+    let c = rest.chars().next()?;
+    let simple = match c {
+        'n' => Some('\n'),   // <--- ¡MAGIA! Convierte \n en salto real
+        't' => Some('\t'),   // <--- Convierte \t en tabulacion
+        'r' => Some('\r'),
+        '0' => Some('\0'),
+        '\\' => Some('\\'),
+        '"' => Some('"'),
+        '$' => Some('$'),
+        _ => None
+    };
+    if let Some(ch) = simple { return Some((ch.to_string(), 1)); }
+    if c == 'u' && rest[1..].starts_with('{') {
+        // \u{1F680}; un \u suelto ("C:\users") se queda tal cual
+        let close = rest.find('}')?;
+        let hex = rest.get(1..close)?.strip_prefix('{')?;
+        let ch = char::from_u32(u32::from_str_radix(hex, 16).ok()?)?;
+        return Some((ch.to_string(), close + 1));
+    }
+    Some((format!("\\{}", c), c.len_utf8()))
+}
+
+fn unescape(inner: &str) -> Option<String> {
+    let mut out = String::new();
+    let mut i = 0;
+    while let Some(c) = inner[i..].chars().next() {
+        if c == '\\' {
+            let (s, used) = unescape_at(&inner[i + 1..])?;
+            out.push_str(&s);
+            i += 1 + used;
+        } else {
+            out.push(c);
+            i += c.len_utf8();
+        }
+    }
+    Some(out)
+}
+
+// El regex consume hasta el primer "${"; desde ahi se escanea a mano el resto del string.
+// Dentro de ${...} se permiten llaves y strings anidados: "${Str.upper("x")}"
+fn lex_template(lex: &mut Lexer<Token>) -> Option<Vec<StrPart>> {
+    let slice = lex.slice();
+    let mut text = unescape(&slice[1..slice.len()-2])?;
+    let rest = lex.remainder();
+    let mut parts = Vec::new();
+    let mut i = 0;
+    let mut in_expr = true;
+    loop {
+        if in_expr {
+            let end = i + find_closing_brace(&rest[i..])?;
+            let mut tokens = Vec::new();
+            for (tok, span) in Token::lexer(&rest[i..end]).spanned() {
+                tokens.push((tok.ok()?, span));
+            }
+            demote_soft_keywords(&mut tokens);
+            if !text.is_empty() { parts.push(StrPart::Text(std::mem::take(&mut text))); }
+            parts.push(StrPart::Expr(tokens));
+            i = end + 1;
+            in_expr = false;
+        }
+        match rest[i..].chars().next()? { // None: string sin cerrar
+            '"' => {
+                if !text.is_empty() { parts.push(StrPart::Text(text)); }
+                lex.bump(i + 1);
+                return Some(parts);
+            },
+            '\\' => {
+                let (s, used) = unescape_at(&rest[i + 1..])?;
+                text.push_str(&s);
+                i += 1 + used;
+            },
+            '$' if rest[i + 1..].starts_with('{') => {
+                i += 2;
+                in_expr = true;
+            },
+            c => {
+                text.push(c);
+                i += c.len_utf8();
+            }
+        }
+    }
+}
+
+// Posicion de la '}' que cierra un ${, saltando strings anidados
+fn find_closing_brace(src: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (idx, c) in src.char_indices() {
+        if in_string {
+            if escaped { escaped = false; }
+            else if c == '\\' { escaped = true; }
+            else if c == '"' { in_string = false; }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(idx),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+// 250ms, 5s, 2m, 1h, 1d -> milisegundos
+fn parse_duration(lex: &mut Lexer<Token>) -> Option<u64> {
+    let slice = lex.slice();
+    let split = slice.find(|c: char| !c.is_ascii_digit())?;
+    let n: u64 = slice[..split].parse().ok()?;
+    let factor = match &slice[split..] {
+        "ms" => 1,
+        "s" => 1_000,
+        "m" => 60_000,
+        "h" => 3_600_000,
+        "d" => 86_400_000,
+        _ => return None
+    };
+    n.checked_mul(factor)
+}
+
+// 512B, 10KB (1000), 10MB, 512KiB (1024), 2GiB -> bytes
+fn parse_bytes(lex: &mut Lexer<Token>) -> Option<u64> {
+    let slice = lex.slice();
+    let split = slice.find(|c: char| !c.is_ascii_digit())?;
+    let n: u64 = slice[..split].parse().ok()?;
+    let factor: u64 = match &slice[split..] {
+        "B" => 1,
+        "KB" => 1_000,
+        "MB" => 1_000_000,
+        "GB" => 1_000_000_000,
+        "TB" => 1_000_000_000_000,
+        "KiB" => 1 << 10,
+        "MiB" => 1 << 20,
+        "GiB" => 1 << 30,
+        "TiB" => 1 << 40,
+        _ => return None
+    };
+    n.checked_mul(factor)
+}
 
 #[derive(Logos, Debug, PartialEq, Clone)]
 pub enum Token {
@@ -23,16 +176,33 @@ pub enum Token {
     #[token("]")] RBracket,
     // -------------------------------------
 
+    // --- Resiliencia: retry N backoff exponential(...) jitter { } until on / timeout ms { } ---
+    #[token("retry")] Retry,
+    #[token("backoff")] Backoff,
+    #[token("jitter")] Jitter,
+    #[token("until")] Until,
+    #[token("timeout")] Timeout,
+    // with_lock "deploy.lock" timeout 30s { }
+    #[token("with_lock")] WithLock,
+    // policy error > loading > unknown > on > off absorbing off;  (o con un bloque { })
+    #[token("policy")] Policy,
+
     #[token("off")] LitOff,
     #[token("on")] LitOn,
     #[token("loading")] LitLoading,
     #[token("error")] LitError,
     #[token("unknown")] LitUnknown,
 
+    // param env: str = "staging";
+    #[token("param")] Param,
+
     #[token("vbit")] TypeVBit,
     #[token("int")] TypeInt, 
     
     #[regex("-?[0-9]+", |lex| lex.slice().parse().ok())] NumberLiteral(i32), 
+    #[regex("-?[0-9]+\\.[0-9]+", |lex| lex.slice().parse().ok())] FloatLiteral(f64),
+    #[regex("[0-9]+(ms|s|m|h|d)", parse_duration)] DurationLiteral(u64),
+    #[regex("[0-9]+(B|KB|MB|GB|TB|KiB|MiB|GiB|TiB)", parse_bytes)] BytesLiteral(u64),
 
     #[token(":=")] AssignPascal,
     #[token("=")]  AssignC,
@@ -60,17 +230,165 @@ pub enum Token {
 
     #[regex("[a-zA-Z_][a-zA-Z0-9_]*", |lex| lex.slice().to_string())] Identifier(String),
     
-    #[regex(r#""(?:[^"\\]|\\.)*""#, |lex| {
+    // Strings sin interpolacion ("...") y raw strings (r"C:\tmp\d+"), estas sin escapes
+    #[regex(r#""(?:[^"\\$]|\\.|\$+[^{"\\$]|\$+\\.)*\$*""#, |lex| {
+        let slice = lex.slice();
+        unescape(&slice[1..slice.len()-1])
+    })]
+    #[regex(r#"r"[^"]*""#, |lex| {
         let slice = lex.slice();
-        let inner = &slice[1..slice.len()-1];
-        inner.replace(r#"\""#, "\"")
-             .replace(r#"\\"#, "\\")
-             .replace(r#"\n"#, "\n")  // <--- ¡MAGIA! Convierte \n en salto real
-             .replace(r#"\t"#, "\t")  // <--- Convierte \t en tabulacion
-    })] 
+        slice[2..slice.len()-1].to_string()
+    })]
     StringLiteral(String),
 
+    // Strings con ${expresion}
+    #[regex(r#""(?:[^"\\$]|\\.|\$+[^{"\\$]|\$+\\.)*\$*\$\{"#, lex_template)]
+    TemplateLiteral(Vec<StrPart>),
+
     #[regex(r"[ \t\n\f\r]+", logos::skip)]
     #[regex(r"//.*", logos::skip)] 
     Error,
-}
\ No newline at end of file
+}
+// --- PALABRAS CLAVE CONTEXTUALES ---
+// retry, backoff, jitter, until, timeout, with_lock, param y policy solo son palabras clave donde la gramatica las espera;
+// en cualquier otro sitio vuelven a ser nombres normales: var timeout := 3;  opts.jitter
+fn soft_keyword(token: &Token) -> Option<&'static str> {
+    match token {
+        Token::Retry => Some("retry"),
+        Token::Backoff => Some("backoff"),
+        Token::Jitter => Some("jitter"),
+        Token::Until => Some("until"),
+        Token::Timeout => Some("timeout"),
+        Token::WithLock => Some("with_lock"),
+        Token::Param => Some("param"),
+        Token::Policy => Some("policy"),
+        _ => None
+    }
+}
+
+fn is_name(token: Option<&Token>) -> bool {
+    matches!(token, Some(Token::Identifier(_))) || token.and_then(soft_keyword).is_some()
+}
+
+fn is_vbit_literal(token: Option<&Token>) -> bool {
+    matches!(token, Some(Token::LitOff | Token::LitOn | Token::LitLoading | Token::LitError | Token::LitUnknown))
+}
+
+// Lo que puede abrir una expresion: retry <n>, timeout <ms>, with_lock <ruta>
+fn starts_value(token: Option<&Token>) -> bool {
+    is_name(token) || matches!(token, Some(
+        Token::NumberLiteral(_) | Token::DurationLiteral(_) | Token::StringLiteral(_) | Token::TemplateLiteral(_) | Token::LParen
+    ))
+}
+
+// Primer '{' de la cabecera que empieza en `start` (retry 3 backoff fixed(1000) jitter {)
+fn header_brace(tokens: &[(Token, Range<usize>)], start: usize) -> Option<usize> {
+    let mut parens = 0;
+    for (idx, (token, _)) in tokens.iter().enumerate().skip(start + 1) {
+        match token {
+            Token::LParen => parens += 1,
+            Token::RParen => parens -= 1,
+            Token::LBrace if parens == 0 => return Some(idx),
+            Token::Semicolon | Token::RBrace if parens == 0 => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+pub fn demote_soft_keywords(tokens: &mut [(Token, Range<usize>)]) {
+    // Hasta que '{' llega la cabecera de un retry / with_lock reconocido
+    let mut retry_header: Option<usize> = None;
+    let mut lock_header: Option<usize> = None;
+    // Por cada '{' abierto: ¿es el cuerpo de un retry? (para reconocer `} until on`)
+    let mut braces: Vec<bool> = Vec::new();
+    let mut closed_retry: Option<usize> = None;
+
+    for idx in 0..tokens.len() {
+        let prev = idx.checked_sub(1).map(|p| &tokens[p].0);
+        let next = tokens.get(idx + 1).map(|(t, _)| t);
+        let after_next = tokens.get(idx + 2).map(|(t, _)| t);
+        let statement_start = matches!(prev, None | Some(Token::Semicolon | Token::LBrace | Token::RBrace));
+        let after_assign = matches!(prev, Some(Token::AssignC | Token::AssignPascal));
+        let in_retry_header = retry_header.is_some_and(|end| idx < end);
+        let in_lock_header = lock_header.is_some_and(|end| idx < end);
+
+        let keyword = match &tokens[idx].0 {
+            Token::Retry => (statement_start || after_assign) && (is_name(next) || matches!(next, Some(Token::NumberLiteral(_)))),
+            Token::Backoff => in_retry_header && matches!(next, Some(Token::Identifier(k)) if k == "fixed" || k == "linear" || k == "exponential"),
+            Token::Jitter => in_retry_header && matches!(next, Some(Token::LBrace)),
+            // Justo tras el cuerpo de un retry siempre es la clausula (`until ready` es un error)
+            Token::Until => closed_retry.is_some_and(|end| end + 1 == idx),
+            Token::Timeout => (statement_start || after_assign || in_lock_header) && starts_value(next) && header_brace(tokens, idx).is_some(),
+            Token::WithLock => (statement_start || after_assign) && starts_value(next),
+            Token::Param => statement_start && is_name(next) && matches!(after_next, Some(Token::Colon)),
+            // policy error > ...;  policy default;  policy absorbing off { }
+            Token::Policy => statement_start && (is_vbit_literal(next)
+                || matches!(next, Some(Token::Identifier(w)) if w == "default" || w == "absorbing")),
+            Token::LBrace => {
+                braces.push(retry_header == Some(idx));
+                continue;
+            },
+            Token::RBrace => {
+                if braces.pop() == Some(true) { closed_retry = Some(idx); }
+                continue;
+            },
+            _ => continue
+        };
+
+        match &tokens[idx].0 {
+            Token::Retry if keyword => retry_header = header_brace(tokens, idx),
+            Token::WithLock if keyword => lock_header = header_brace(tokens, idx),
+            _ => {}
+        }
+        if !keyword && let Some(word) = soft_keyword(&tokens[idx].0) {
+            tokens[idx].0 = Token::Identifier(word.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lex(code: &str) -> Vec<Token> {
+        Token::lexer(code).map(|t| t.unwrap()).collect()
+    }
+
+    #[test]
+    fn unit_literals() {
+        assert_eq!(lex("250ms 5s 2m 1h 1d"), vec![
+            Token::DurationLiteral(250), Token::DurationLiteral(5_000), Token::DurationLiteral(120_000),
+            Token::DurationLiteral(3_600_000), Token::DurationLiteral(86_400_000),
+        ]);
+        assert_eq!(lex("512B 10KB 4MiB"), vec![
+            Token::BytesLiteral(512), Token::BytesLiteral(10_000), Token::BytesLiteral(4 << 20),
+        ]);
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(lex(r#""a\tb\n\$""#), vec![Token::StringLiteral("a\tb\n$".to_string())]);
+        assert_eq!(lex(r#""\u{1F600}""#), vec![Token::StringLiteral("😀".to_string())]);
+        assert_eq!(lex(r#"r"C:\tmp\d+""#), vec![Token::StringLiteral(r"C:\tmp\d+".to_string())]);
+    }
+
+    #[test]
+    fn bare_u_escape_is_kept_as_written() {
+        assert_eq!(lex(r#""C:\users\me""#), vec![Token::StringLiteral(r"C:\users\me".to_string())]);
+        assert_eq!(lex(r#""\d+\u""#), vec![Token::StringLiteral(r"\d+\u".to_string())]);
+    }
+
+    #[test]
+    fn template_parts() {
+        let Some(Ok(Token::TemplateLiteral(parts))) = Token::lexer(r#""id ${n + 1}!""#).next() else { panic!() };
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0], StrPart::Text("id ".to_string()));
+        assert_eq!(parts[2], StrPart::Text("!".to_string()));
+    }
+
+    #[test]
+    fn overflowing_unit_literal_is_a_lexer_error() {
+        assert!(Token::lexer("99999999999999999d").any(|t| t.is_err()));
+    }
+}
@@ -1,24 +1,100 @@
 use serde_json::Value;
 use colored::*;
+use chrono::FixedOffset;
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum VasoType {
     Int(i32),
+    // Decimales: 0.5, Json floats, medias y porcentajes
+    Float(f64),
     VBit(u8, String), 
     Str(String),
     Json(Value),
     Function(usize, Vec<String>), 
     // --- NUEVO: Listas ---
     List(Vec<VasoType>),
+    // --- Unidades: milisegundos y bytes ---
+    Duration(u64),
+    Bytes(u64),
+    // --- Fechas: instante con offset (Time.now, Time.parse) ---
+    DateTime(chrono::DateTime<FixedOffset>),
+    // --- Mapas / registros: {cwd: "/app", env: {CI: "1"}} y resultados de Sys.exec ---
+    Map(BTreeMap<String, VasoType>),
+    // --- Handle de un proceso en segundo plano (Proc.spawn) ---
+    Proc(usize),
+}
+
+pub fn vbit_name(level: u8) -> &'static str {
+    match level { 0 => "off", 1 => "on", 2 => "loading", 3 => "error", _ => "unknown" }
+}
+
+impl VasoType {
+    // Un registro con campo `status` (resultado de Sys.exec) se comporta como su VBit
+    // en operaciones, match e if: build + test
+    pub fn state(&self) -> &VasoType {
+        match self {
+            VasoType::Map(fields) => match fields.get("status") {
+                Some(status @ VasoType::VBit(..)) => status,
+                _ => self,
+            },
+            other => other,
+        }
+    }
+
+    // Texto plano para "${...}": sin colores, y un error muestra directamente su mensaje
+    pub fn render(&self) -> String {
+        match self {
+            VasoType::VBit(3, msg) if !msg.is_empty() => msg.clone(),
+            VasoType::VBit(lvl, msg) if msg.is_empty() => vbit_name(*lvl).to_string(),
+            VasoType::VBit(lvl, msg) => format!("{}({})", vbit_name(*lvl), msg),
+            other => other.to_string(),
+        }
+    }
+}
+
+// 90000 -> "1m30s", 250 -> "250ms"
+fn format_duration(ms: u64) -> String {
+    if ms == 0 { return "0ms".to_string(); }
+    let units = [("d", 86_400_000), ("h", 3_600_000), ("m", 60_000), ("s", 1_000), ("ms", 1)];
+    let mut rest = ms;
+    let mut out = String::new();
+    for (name, size) in units {
+        if rest >= size {
+            out.push_str(&format!("{}{}", rest / size, name));
+            rest %= size;
+        }
+    }
+    out
+}
+
+// Usa la unidad exacta mas grande (10MB, 512KiB); si no hay, binaria con decimales
+fn format_bytes(b: u64) -> String {
+    let units: [(&str, u64); 8] = [
+        ("TiB", 1 << 40), ("TB", 1_000_000_000_000), ("GiB", 1 << 30), ("GB", 1_000_000_000),
+        ("MiB", 1 << 20), ("MB", 1_000_000), ("KiB", 1 << 10), ("KB", 1_000),
+    ];
+    for (name, size) in units {
+        if b >= size && b.is_multiple_of(size) { return format!("{}{}", b / size, name); }
+    }
+    for (name, size) in units.iter().filter(|(n, _)| n.ends_with("iB")) {
+        if b >= *size { return format!("{:.2}{}", b as f64 / *size as f64, name); }
+    }
+    format!("{}B", b)
 }
 
 impl std::fmt::Display for VasoType {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             VasoType::Int(n) => write!(f, "{}", n),
+            // {:?} conserva el ".0": 3.0 no se confunde con el Int 3
+            VasoType::Float(x) => write!(f, "{:?}", x),
             VasoType::Str(s) => write!(f, "{}", s),
             VasoType::Json(v) => write!(f, "{}", v),
             VasoType::Function(_, args) => write!(f, "fn({:?})", args),
+            VasoType::Duration(ms) => write!(f, "{}", format_duration(*ms)),
+            VasoType::Bytes(b) => write!(f, "{}", format_bytes(*b)),
+            VasoType::DateTime(dt) => write!(f, "{}", crate::datetime::to_rfc3339(dt)),
             // Formato de lista: [1, 2, 3]
             VasoType::List(vec) => {
                 write!(f, "[")?;
@@ -28,6 +104,15 @@ impl std::fmt::Display for VasoType {
                 }
                 write!(f, "]")
             },
+            VasoType::Proc(id) => write!(f, "proc(pid {})", crate::process::proc_pid(*id)),
+            VasoType::Map(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, val)) in fields.iter().enumerate() {
+                    if i > 0 { write!(f, ", ")?; }
+                    write!(f, "{}: {}", key, val)?;
+                }
+                write!(f, "}}")
+            },
             VasoType::VBit(v, msg) => {
                 let s = match v {
                     0 => "off".normal(),
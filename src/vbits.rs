@@ -0,0 +1,127 @@
+use crate::types::{VasoType, vbit_name};
+use crate::logic::dominates;
+
+// --- V-BITS: VBit.worst / best / count / quorum / ratio ---
+// Todo se ordena con la politica activa (por defecto error > unknown > loading > on > off).
+// Los registros de Sys.exec cuentan por su status. Aqui un `error` es un dato mas,
+// no algo que aborte la llamada: VBit.count(checks, error).
+
+fn err(msg: String) -> VasoType {
+    VasoType::VBit(3, msg)
+}
+
+// Nivel y mensaje de cada elemento; algo sin estado es un error de tipo
+fn states<'a>(func: &str, items: &'a [VasoType]) -> Result<Vec<(u8, &'a str)>, VasoType> {
+    items.iter().map(|item| match item.state() {
+        VasoType::VBit(lvl, msg) => Ok((*lvl, msg.as_str())),
+        other => Err(err(format!("VBit.{}: {} is not a V-Bit", func, other.render()))),
+    }).collect()
+}
+
+fn level_of(func: &str, v: &VasoType) -> Result<u8, VasoType> {
+    match v.state() {
+        VasoType::VBit(lvl, _) => Ok(*lvl),
+        VasoType::Str(name) => (0..=4).find(|l| vbit_name(*l) == name)
+            .ok_or_else(|| err(format!("VBit.{}: unknown state '{}'", func, name))),
+        other => Err(err(format!("VBit.{}: expected a state, got {}", func, other.render()))),
+    }
+}
+
+// Un elemento completo (con su mensaje) segun la prioridad; la lista vacia es unknown
+fn pick(func: &str, items: &[VasoType], worst: bool) -> VasoType {
+    let levels = match states(func, items) {
+        Ok(l) => l,
+        Err(e) => return e,
+    };
+    // worst: el que gana todas las combinaciones (como la suma); best: el que las pierde.
+    // En empate se queda el primero.
+    let chosen = levels.iter().fold(None, |acc: Option<&(u8, &str)>, item| match acc {
+        Some(current) if worst && dominates(current.0, item.0) => Some(current),
+        Some(current) if !worst && dominates(item.0, current.0) => Some(current),
+        _ => Some(item),
+    });
+    match chosen {
+        Some((lvl, msg)) => VasoType::VBit(*lvl, msg.to_string()),
+        None => VasoType::VBit(4, format!("VBit.{} of an empty list", func)),
+    }
+}
+
+// on si hay al menos n en on; loading si los loading/unknown aun pueden llegar; error si no
+fn quorum(items: &[VasoType], n: i32) -> VasoType {
+    let levels = match states("quorum", items) {
+        Ok(l) => l,
+        Err(e) => return e,
+    };
+    let count = |lvl: u8| levels.iter().filter(|(l, _)| *l == lvl).count() as i32;
+    let (on, pending) = (count(1), count(2) + count(4));
+    let summary = format!("{}/{} on, {} pending, quorum {}", on, levels.len(), pending, n);
+    if on >= n { VasoType::VBit(1, "".to_string()) }
+    else if on + pending >= n { VasoType::VBit(2, summary) }
+    else { err(format!("Quorum lost: {}", summary)) }
+}
+
+pub fn call(func: &str, args: &[VasoType]) -> VasoType {
+    use VasoType::{Int, List};
+    match (func, args) {
+        ("worst", [List(items)]) => pick(func, items, true),
+        ("best", [List(items)]) => pick(func, items, false),
+        ("count", [List(items), state]) => match (states(func, items), level_of(func, state)) {
+            (Ok(levels), Ok(want)) => Int(levels.iter().filter(|(l, _)| *l == want).count() as i32),
+            (Err(e), _) | (_, Err(e)) => e,
+        },
+        ("quorum", [List(items), Int(n)]) => quorum(items, *n),
+        // Fraccion en on (o en el estado pedido): VBit.ratio(checks) -> 0.8
+        ("ratio", [List(items), rest @ ..]) if rest.len() <= 1 => {
+            let want = match rest.first().map(|s| level_of(func, s)) {
+                None => 1,
+                Some(Ok(lvl)) => lvl,
+                Some(Err(e)) => return e,
+            };
+            match states(func, items) {
+                Ok(levels) if levels.is_empty() => VasoType::VBit(4, "VBit.ratio of an empty list".to_string()),
+                Ok(levels) => VasoType::Float(levels.iter().filter(|(l, _)| *l == want).count() as f64 / levels.len() as f64),
+                Err(e) => e,
+            }
+        },
+        ("from_int", [Int(n)]) => match u8::try_from(*n) {
+            Ok(lvl) if lvl <= 4 => VasoType::VBit(lvl, "".to_string()),
+            _ => err(format!("VBit.from_int: {} is not a state (0-4)", n)),
+        },
+        ("to_int", [v]) => match level_of(func, v) {
+            Ok(lvl) => Int(lvl as i32),
+            Err(e) => e,
+        },
+        ("worst" | "best" | "count" | "quorum" | "ratio" | "from_int" | "to_int", _) =>
+            err(format!("VBit.{} got invalid args {:?}", func, args)),
+        _ => err(format!("VBit.{} not found", func)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn vbits(levels: &[u8]) -> Vec<VasoType> {
+        levels.iter().map(|l| VasoType::VBit(*l, "".to_string())).collect()
+    }
+
+    #[test]
+    fn quorum_reached_pending_or_lost() {
+        assert_eq!(quorum(&vbits(&[1, 1, 0]), 2), VasoType::VBit(1, "".to_string()));
+        assert_eq!(quorum(&vbits(&[1, 2, 4, 0]), 3), VasoType::VBit(2, "1/4 on, 2 pending, quorum 3".to_string()));
+        assert_eq!(quorum(&vbits(&[1, 3, 0, 2]), 3), VasoType::VBit(3, "Quorum lost: 1/4 on, 1 pending, quorum 3".to_string()));
+        assert_eq!(quorum(&[], 0), VasoType::VBit(1, "".to_string()));
+    }
+
+    #[test]
+    fn quorum_counts_records_by_status() {
+        let record = |lvl: u8| VasoType::Map(BTreeMap::from([("status".to_string(), VasoType::VBit(lvl, "".to_string()))]));
+        assert_eq!(call("quorum", &[VasoType::List(vec![record(1), record(1), record(3)]), VasoType::Int(2)]), VasoType::VBit(1, "".to_string()));
+    }
+
+    #[test]
+    fn quorum_rejects_values_without_state() {
+        assert_eq!(quorum(&[VasoType::Int(1)], 1), VasoType::VBit(3, "VBit.quorum: 1 is not a V-Bit".to_string()));
+    }
+}
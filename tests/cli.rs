@@ -0,0 +1,539 @@
+// Pruebas de punta a punta: escriben un script .vs temporal y ejecutan el binario vaso.
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn script(name: &str, code: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("vaso-cli-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    fs::write(&path, code).unwrap();
+    path
+}
+
+fn run(path: &PathBuf, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_vaso"))
+        .arg(path)
+        .args(args)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap()
+}
+
+fn stdout(out: &Output) -> String {
+    String::from_utf8_lossy(&out.stdout).to_string()
+}
+
+#[test]
+fn nested_retry_restores_outer_attempt() {
+    let path = script("nested_retry.vs", r#"
+var outer := retry 2 {
+    var inner := retry 3 {
+        var x := error;
+    };
+    print(attempt);
+    var y := error;
+};
+"#);
+    let text = stdout(&run(&path, &[]));
+    assert!(text.contains("\n1\n2\n"), "{}", text);
+}
+
+#[test]
+fn retry_words_are_names_outside_their_construct() {
+    let path = script("soft_retry.vs", r#"
+var retry := 3;
+var backoff := 7;
+var until := 9;
+print(retry);
+print(backoff);
+print(until);
+"#);
+    let text = stdout(&run(&path, &[]));
+    assert!(text.contains("\n3\n7\n9\n"), "{}", text);
+    assert!(!text.contains("ERROR"), "{}", text);
+}
+
+#[test]
+fn until_without_state_is_a_parse_error() {
+    let path = script("until_ready.vs", r#"
+var r := retry 2 {
+    print("body ran");
+} until ready;
+print("after");
+"#);
+    let text = stdout(&run(&path, &[]));
+    assert!(text.contains("until expects a state"), "{}", text);
+    assert!(!text.contains("body ran"), "{}", text);
+    assert!(text.contains("after"), "{}", text);
+}
+
+#[test]
+fn timeout_block_cancels_and_yields_error() {
+    let path = script("timeout_block.vs", r#"
+var base := 50ms;
+var slow := timeout (base + 50ms) {
+    Time.sleep(5000);
+};
+print(slow);
+var fast := timeout 1000 {
+    var x := 5;
+};
+print(fast);
+"#);
+    let text = stdout(&run(&path, &[]));
+    assert!(text.contains("error(\"Timeout after 100ms\")\n5\n"), "{}", text);
+}
+
+#[test]
+fn timeout_unwinds_nested_retry_attempt() {
+    let path = script("timeout_retry.vs", r#"
+var outer := retry 2 {
+    var t := timeout 100 {
+        var inner := retry 5 backoff fixed(1000) {
+            var x := error;
+        };
+    };
+    print(attempt);
+    var y := error;
+};
+"#);
+    let text = stdout(&run(&path, &[]));
+    assert!(text.contains("\n1\n2\n"), "{}", text);
+}
+
+#[test]
+fn timeout_is_a_name_outside_its_construct() {
+    let path = script("soft_timeout.vs", r#"
+var timeout := 3;
+print(timeout);
+"#);
+    let text = stdout(&run(&path, &[]));
+    assert!(text.contains("\n3\n"), "{}", text);
+    assert!(!text.contains("ERROR"), "{}", text);
+}
+
+#[test]
+fn script_timeout_exits_with_124() {
+    let path = script("script_timeout.vs", "Time.sleep(5000);\nprint(\"finished\");\n");
+    let out = Command::new(env!("CARGO_BIN_EXE_vaso"))
+        .args(["--timeout", "100"])
+        .arg(&path)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(124));
+    assert!(!stdout(&out).contains("finished"));
+}
+
+#[test]
+fn unit_literals_print_in_their_largest_exact_unit() {
+    let path = script("units.vs", r#"
+var d := 1m;
+d += 30s;
+print(d);
+var size := 512KiB;
+size *= 2;
+print(size);
+var ratio := 10MB;
+ratio /= 512KB;
+print(ratio);
+"#);
+    let text = stdout(&run(&path, &[]));
+    assert!(text.contains("\n1m30s\n1MiB\nerror(\"Inexact Bytes Ratio\")\n"), "{}", text);
+}
+
+#[test]
+fn datetime_arithmetic_and_offsets() {
+    let path = script("datetime.vs", r#"
+var t := Time.parse("2025-03-01T10:00:00Z");
+t += 90m;
+print(t);
+var local := Time.with_offset(t, "+02:00");
+print(local);
+var start := Time.parse("2025-03-01T09:00:00Z");
+var gap := t;
+gap -= start;
+print(gap);
+var clock := Time.monotonic();
+var elapsed := Time.since(clock);
+print(elapsed);
+"#);
+    let text = stdout(&run(&path, &[]));
+    assert!(text.contains("\n2025-03-01T11:30:00Z\n2025-03-01T13:30:00+02:00\n2h30m\n"), "{}", text);
+}
+
+#[test]
+fn string_interpolation_and_escapes() {
+    let path = script("interpolation.vs", r#"
+var id := 7;
+var status := loading;
+var retry := 2;
+var p := "C:\users\me";
+print("node ${id + 1} is ${status}, retry ${retry}");
+print(p);
+print("\u{1F600} \${id}");
+"#);
+    let text = stdout(&run(&path, &[]));
+    assert!(text.contains("\nnode 8 is loading, retry 2\nC:\\users\\me\n😀 ${id}\n"), "{}", text);
+    assert!(!text.contains("ERROR"), "{}", text);
+}
+
+#[test]
+fn failed_exec_record_compares_by_status() {
+    let path = script("exec_record.vs", r#"
+var res := Sys.exec("false");
+if res == error {
+    print("is error");
+}
+if res != on {
+    print("is not on");
+}
+if res != error {
+    print("wrong: != error");
+}
+if res == on {
+    print("wrong: == on");
+}
+var ok := Sys.exec("true");
+if ok == on {
+    print("ok is on");
+}
+print(res.code);
+"#);
+    let text = stdout(&run(&path, &[]));
+    assert!(text.contains("\nis error\nis not on\nok is on\n1\n"), "{}", text);
+    assert!(!text.contains("wrong"), "{}", text);
+}
+
+#[test]
+fn streaming_prefixes_lines_and_tees_to_log() {
+    let log = std::env::temp_dir().join(format!("vaso-stream-{}.log", std::process::id()));
+    let _ = fs::remove_file(&log);
+    let path = script("stream.vs", &format!(r#"
+var res := Sys.exec("sh", ["-c", "echo one; echo two >&2; echo three"], {{stream: "build", log: "{}"}});
+print(res.stdout);
+"#, log.display()));
+    let out = run(&path, &[]);
+    let text = stdout(&out);
+    assert!(text.contains("[build] one\n[build] three\n"), "{}", text);
+    assert!(String::from_utf8_lossy(&out.stderr).contains("[build] two"));
+    // Lo capturado sigue en el registro
+    assert!(text.contains("one\nthree\n\n"), "{}", text);
+    let logged = fs::read_to_string(&log).unwrap();
+    assert!(logged.contains("[build] one") && logged.contains("[build] two") && logged.contains("[build] three"), "{}", logged);
+    let _ = fs::remove_file(&log);
+}
+
+#[cfg(unix)]
+#[test]
+fn sigterm_stops_background_processes() {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+    let path = script("signal_cleanup.vs", r#"
+var h := Proc.spawn("sleep", "30");
+var pid := Proc.pid(h);
+print(pid);
+Time.sleep(10s);
+"#);
+    let mut vaso = Command::new(env!("CARGO_BIN_EXE_vaso"))
+        .arg(&path)
+        .env("NO_COLOR", "1")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut lines = BufReader::new(vaso.stdout.take().unwrap()).lines();
+    let pid = lines.find_map(|l| l.ok()?.trim().parse::<u32>().ok()).unwrap();
+    Command::new("kill").args(["-TERM", &vaso.id().to_string()]).status().unwrap();
+    assert_eq!(vaso.wait().unwrap().code(), Some(143));
+    let alive = Command::new("kill").args(["-0", &pid.to_string()]).stderr(Stdio::null()).status().unwrap();
+    assert!(!alive.success(), "background child {} survived", pid);
+}
+
+#[test]
+fn pipe_sh_and_quote() {
+    let path = script("pipe_sh.vs", r#"
+var hits := Sys.pipe([["printf", "a\nb500\nc500\n"], ["grep", "500"], ["wc", "-l"]]);
+print(hits.stdout);
+var failed := Sys.pipe([["sh", "-c", "exit 3"], ["cat"]]);
+print(failed.code);
+print(failed.stages);
+var echoed := Sys.sh("echo \"$1|$2\"", ["a b", "$HOME"]);
+print(echoed.stdout);
+var q := Sys.quote("it's");
+print(q);
+"#);
+    let text = stdout(&run(&path, &[]));
+    assert!(text.contains("2\n"), "{}", text);
+    assert!(text.contains("\n3\n[3, 0]\na b|$HOME\n\n'it'\\''s'\n"), "{}", text);
+}
+
+#[test]
+fn help_lists_params_and_keeps_url_defaults() {
+    let path = script("params_help.vs", r#"
+param url: str = "https://example.com/api"; // endpoint
+param msg: str = "hi";
+print("msg=${msg}");
+"#);
+    let text = stdout(&run(&path, &["--help"]));
+    assert!(text.contains("Usage:"), "{}", text);
+    assert!(text.contains("endpoint (default: \"https://example.com/api\")"), "{}", text);
+
+    // --help como valor de --msg no es una peticion de ayuda
+    let text = stdout(&run(&path, &["--msg", "--help"]));
+    assert!(text.contains("msg=--help\n"), "{}", text);
+}
+
+#[test]
+fn help_flag_is_positional_without_params() {
+    let path = script("no_params.vs", r#"print("arg=${Sys.arg(0)}");"#);
+    let text = stdout(&run(&path, &["--help"]));
+    assert!(text.contains("arg=--help\n"), "{}", text);
+}
+
+#[test]
+fn param_is_a_name_outside_declarations() {
+    let path = script("soft_param.vs", r#"
+param env: str = "staging";
+var param := 4;
+print(param);
+print(env);
+"#);
+    let text = stdout(&run(&path, &["--env", "prod"]));
+    assert!(text.contains("\n4\nprod\n"), "{}", text);
+}
+
+#[test]
+fn file_operations() {
+    let dir = std::env::temp_dir().join(format!("vaso-files-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let d = dir.display();
+    let path = script("files.vs", &format!(r#"
+File.mkdir("{d}/dist/js");
+File.write("{d}/dist/js/app.js", "x");
+var copied := File.copy("{d}/dist/js/app.js", "{d}/app.js");
+print(copied);
+var missing := File.copy("{d}/nope.js", "{d}/out.js");
+print(missing);
+var found := File.glob("{d}/dist/**/*.js");
+print(found);
+var kept := File.delete("{d}/dist");
+print(kept);
+var gone := File.delete("{d}/dist", {{recursive: on}});
+print(gone);
+var exists := File.exists("{d}/dist");
+print(exists);
+"#));
+    let text = stdout(&run(&path, &[]));
+    assert!(text.contains(&format!("\non\nerror(\"File.copy: '{d}/nope.js' -> '{d}/out.js': ")), "{}", text);
+    assert!(text.contains(&format!("[{d}/dist/js/app.js]\n")), "{}", text);
+    assert!(text.contains(&format!("error(\"File.delete: '{d}/dist': directory not empty (use {{recursive: on}})\")\non\noff\n")), "{}", text);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[cfg(unix)]
+#[test]
+fn script_dir_ignores_the_working_directory() {
+    let path = script("script_dir.vs", r#"
+var dir := Path.script_dir();
+print(dir);
+var joined := Path.join(dir, "config.json");
+print(joined);
+"#);
+    let out = Command::new(env!("CARGO_BIN_EXE_vaso"))
+        .arg(&path)
+        .current_dir("/")
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap();
+    let dir = path.parent().unwrap().display().to_string();
+    assert!(stdout(&out).contains(&format!("\n{}\n{}/config.json\n", dir, dir)), "{}", stdout(&out));
+}
+
+#[test]
+fn with_lock_times_out_while_another_run_holds_it() {
+    let lock = std::env::temp_dir().join(format!("vaso-lock-{}.lock", std::process::id()));
+    let _ = fs::remove_file(&lock);
+    let holder = script("lock_holder.vs", &format!(r#"
+var done := with_lock "{}" {{
+    print("holding");
+    Time.sleep(3s);
+}};
+"#, lock.display()));
+    let waiter = script("lock_waiter.vs", &format!(r#"
+var with_lock := 2;
+print(with_lock);
+var base := 100ms;
+var blocked := with_lock "{}" timeout base + 100ms {{
+    print("wrong");
+}};
+print(blocked);
+"#, lock.display()));
+    let mut first = Command::new(env!("CARGO_BIN_EXE_vaso"))
+        .arg(&holder)
+        .env("NO_COLOR", "1")
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    // Esperamos a que el primero tenga el lock
+    let mut out = std::io::BufReader::new(first.stdout.take().unwrap());
+    let mut line = String::new();
+    while !line.contains("holding") {
+        line.clear();
+        assert!(std::io::BufRead::read_line(&mut out, &mut line).unwrap() > 0);
+    }
+    let text = stdout(&run(&waiter, &[]));
+    let _ = first.kill();
+    let _ = first.wait();
+    assert!(text.contains("\n2\n"), "{}", text);
+    assert!(text.contains(&format!("error(\"Lock '{}' held by PID {} (waited 200ms)\")", lock.display(), first.id())), "{}", text);
+    assert!(!text.contains("wrong"), "{}", text);
+    let _ = fs::remove_file(&lock);
+}
+
+#[test]
+fn json_paths_get_and_set() {
+    let path = script("json_paths.vs", r#"
+var doc := Json.parse("{\"spec\": {\"containers\": [{\"image\": \"app:1\"}, {\"image\": \"proxy:2\"}]}}");
+var last := Json.get(doc, "spec.containers[-1].image");
+print(last);
+var missing := Json.get(doc, "spec.replicas");
+print(missing);
+var updated := Json.set(doc, "spec.replicas", 3);
+var text := Json.stringify(Json.get(updated, "spec.replicas"));
+print(text);
+"#);
+    let text = stdout(&run(&path, &[]));
+    assert!(text.contains("\nproxy:2\nerror(\"Field 'spec.replicas' not found\")\n3\n"), "{}", text);
+}
+
+#[test]
+fn json_validate_against_a_schema() {
+    let path = script("schema.vs", r#"
+var schema := Json.parse("{\"required\": [\"name\"], \"properties\": {\"replicas\": {\"type\": \"integer\", \"minimum\": 1}}}");
+var good := Json.validate(Json.parse("{\"name\": \"api\", \"replicas\": 2}"), schema);
+print(good);
+var bad := Json.validate(Json.parse("{\"replicas\": 0}"), schema);
+print(bad);
+"#);
+    let text = stdout(&run(&path, &[]));
+    assert!(text.contains("\non\nerror(\"2 schema violations: /name: required property missing | /replicas: 0 is less than minimum 1\")\n"), "{}", text);
+}
+
+#[test]
+fn yaml_and_toml_share_json_paths() {
+    let path = script("yaml_toml.vs", r#"
+var docs := Yaml.parse_all("kind: Service\nmetadata:\n  name: api\n---\nkind: Deployment\nmetadata:\n  name: web\n");
+for doc in docs {
+    print("${Json.get(doc, "kind")}: ${Json.get(doc, "metadata.name")}");
+}
+var cargo := Toml.parse("[package]\nversion = \"1.2.0\"\n");
+var version := Json.get(cargo, "package.version");
+print(version);
+"#);
+    let text = stdout(&run(&path, &[]));
+    assert!(text.contains("\nService: api\nDeployment: web\n1.2.0\n"), "{}", text);
+}
+
+#[test]
+fn csv_table_keeps_header_order_and_leading_zeros() {
+    let dir = std::env::temp_dir().join("vaso_cli_csv");
+    fs::create_dir_all(&dir).unwrap();
+    let src = dir.join("in.csv");
+    let dst = dir.join("out.csv");
+    fs::write(&src, "zone,code,amount\nnorth,007,10\nbroken\nsouth,012,20\n").unwrap();
+    let path = script("csv_table.vs", &format!(r#"
+var sales := Csv.read("{}", {{table: on}});
+print(sales.columns);
+var saved := Csv.write("{}", sales);
+print(saved);
+"#, src.display(), dst.display()));
+    let text = stdout(&run(&path, &[]));
+    assert!(text.contains("[zone, code, amount]\non(\"1 error rows skipped\")\n"), "{}", text);
+    assert_eq!(fs::read_to_string(&dst).unwrap(), "zone,code,amount\nnorth,007,10\nsouth,012,20\n");
+}
+
+#[test]
+fn regex_in_conditions() {
+    let path = script("regex.vs", r#"
+var lines := ["ok [api] fine", "ERROR [db-1] down", "FATAL [cache] gone"];
+var alerts := 0;
+for line in lines {
+    if Regex.match(r"ERROR|FATAL", line) {
+        var caps := Regex.captures(r"\[(?P<host>[\w-]+)\]", line);
+        print("alert on ${caps.host}");
+        alerts += 1;
+    }
+}
+if alerts != 0 { print("alerts: ${alerts}"); }
+"#);
+    let text = stdout(&run(&path, &[]));
+    assert!(text.contains("\nalert on db-1\nalert on cache\nalerts: 2\n"), "{}", text);
+}
+
+#[test]
+fn list_functions_take_error_as_an_item() {
+    let path = script("list_errors.vs", r#"
+var xs := [on, List.pop([]), off];
+print(xs.contains(error));
+print(xs.index_of(error));
+print(List.push([1], error));
+print(List.len(List.pop([])));
+"#);
+    let text = stdout(&run(&path, &[]));
+    assert!(text.contains("on\n1\n[1, error(\"Generic Error\")]\nerror(\"List.pop on an empty list\")\n"), "{}", text);
+}
+
+#[test]
+fn list_methods_as_statements_update_the_variable() {
+    let path = script("list_statements.vs", r#"
+var queue := [3, 1];
+queue.push(2);
+queue.sort();
+var copy := queue.reverse();
+print(queue);
+print(copy);
+"#);
+    let text = stdout(&run(&path, &[]));
+    assert!(text.contains("\n[1, 2, 3]\n[3, 2, 1]\n"), "{}", text);
+}
+
+#[test]
+fn policy_statement_and_block() {
+    let path = script("policy.vs", r#"
+var policy := "strict";
+var probe := unknown + on;
+print(probe);
+policy error > loading > on > unknown > off;
+probe := unknown + on;
+print(probe);
+policy absorbing off {
+    var gate := on + off;
+    print(gate);
+}
+var after := on + off;
+print(after);
+print(policy);
+"#);
+    let text = stdout(&run(&path, &[]));
+    assert!(text.contains("\nunknown\non\noff\non\nstrict\n"), "{}", text);
+}
+
+#[test]
+fn seed_flag_makes_math_random_reproducible() {
+    let path = script("seeded.vs", r#"
+var picks := [Math.random(1, 1000), Math.random(1, 1000), Math.choice(["a", "b", "c"])];
+print(picks);
+print(Math.percentile([4, 1, 3, 2], 50));
+"#);
+    let seeded = || stdout(&Command::new(env!("CARGO_BIN_EXE_vaso"))
+        .args(["--seed", "42"])
+        .arg(&path)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap());
+    let (a, b) = (seeded(), seeded());
+    assert_eq!(a, b);
+    assert!(a.contains("\n2.5\n"), "{}", a);
+}